@@ -40,4 +40,67 @@ pub enum ErrorCode {
 
     #[msg("Invalid fee recipient")]
     InvalidFeeRecipient,
+
+    #[msg("No pending authority transfer")]
+    NoPendingAuthority,
+
+    #[msg("Signer is not the pending authority")]
+    InvalidPendingAuthority,
+
+    #[msg("Invalid role permissions")]
+    InvalidPermissions,
+
+    #[msg("This path is paused")]
+    ProgramPaused,
+
+    #[msg("Invalid pause flags - guardian can only add pause bits")]
+    InvalidPauseFlags,
+
+    #[msg("Account is already on the current layout version")]
+    AlreadyMigrated,
+
+    #[msg("Request ID is empty or too long")]
+    InvalidRequestId,
+
+    #[msg("Attestation request can't be refunded yet")]
+    RequestNotExpired,
+
+    #[msg("Schema doesn't match the attestation request")]
+    RequestSchemaMismatch,
+
+    #[msg("Attestation subject doesn't match the requesting user")]
+    RequestUserMismatch,
+
+    #[msg("Schema name is too long")]
+    SchemaNameTooLong,
+
+    #[msg("Account is not a valid SAS schema")]
+    InvalidSchemaAccount,
+
+    #[msg("Schema is already active")]
+    SchemaAlreadyActive,
+
+    #[msg("Attestation data is shorter than the schema layout")]
+    DataTruncated,
+
+    #[msg("Schema layout uses an unsupported field type")]
+    UnsupportedFieldType,
+
+    #[msg("Bool field must be 0 or 1")]
+    InvalidBool,
+
+    #[msg("String field is not valid UTF-8")]
+    InvalidString,
+
+    #[msg("String field is too long")]
+    StringTooLong,
+
+    #[msg("Field value is out of range")]
+    FieldOutOfRange,
+
+    #[msg("Account is not a valid SAS credential")]
+    InvalidCredentialAccount,
+
+    #[msg("Arithmetic overflow")]
+    Overflow,
 }
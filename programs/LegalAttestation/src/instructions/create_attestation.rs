@@ -7,6 +7,7 @@ use crate::state::*;
 use crate::error::ErrorCode;
 
 #[derive(Accounts)]
+#[instruction(nonce: Pubkey)]
 pub struct CreateAttestation<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -14,20 +15,66 @@ pub struct CreateAttestation<'info> {
     #[account(
         seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
         bump = config.bump,
-        constraint = config.is_admin_or_operator(&authority.key()) @ ErrorCode::Unauthorized
+        constraint = config.has_permission(&authority.key(), role.as_deref(), permissions::ATTEST) @ ErrorCode::Unauthorized,
+        constraint = !config.is_paused(pause_flags::OPERATOR) @ ErrorCode::ProgramPaused
     )]
     pub config: Account<'info, AttestationConfig>,
 
+    /// Role granting the signer permission (not needed for admin / operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    /// CHECK: PDA that owns the SAS credential and schemas, signs SAS CPIs
+    #[account(
+        seeds = [CREDENTIAL_AUTHORITY_PREFIX.as_bytes()],
+        bump
+    )]
+    pub credential_authority: UncheckedAccount<'info>,
+
     /// CHECK: SAS Credential account - required for creating attestations
     pub credential: UncheckedAccount<'info>,
 
-    /// CHECK: SAS Schema account
+    /// CHECK: SAS Schema account - registered via schema_record, layout read to validate data
     pub schema: UncheckedAccount<'info>,
 
+    /// Registry entry of the schema, must be active
+    #[account(
+        seeds = [SCHEMA_RECORD_PREFIX.as_bytes(), schema.key().as_ref()],
+        bump = schema_record.bump,
+        constraint = schema_record.active @ ErrorCode::SchemaInactive
+    )]
+    pub schema_record: Account<'info, SchemaRecord>,
+
     /// CHECK: SAS Attestation account - will be created by SAS program
     #[account(mut)]
     pub attestation: UncheckedAccount<'info>,
 
+    /// Paid request this attestation fulfils, its escrowed fee is released to fee_recipient
+    /// and the account is closed, rent goes back to the user
+    #[account(
+        mut,
+        close = user,
+        seeds = [ATTESTATION_REQUEST_PREFIX.as_bytes(), request.user.as_ref(), &request_id_seed(&request.request_id)],
+        bump = request.bump,
+        constraint = request.schema == schema.key() @ ErrorCode::RequestSchemaMismatch,
+        constraint = request.user == nonce @ ErrorCode::RequestUserMismatch
+    )]
+    pub request: Account<'info, AttestationRequest>,
+
+    /// User who paid the request, receives its rent
+    #[account(
+        mut,
+        constraint = user.key() == request.user @ ErrorCode::RequestUserMismatch
+    )]
+    pub user: SystemAccount<'info>,
+
+    /// Fee recipient address
+    /// CHECK: Validated from config account
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == config.fee_recipient @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub fee_recipient: UncheckedAccount<'info>,
+
     /// CHECK: SAS Program
     #[account(
         constraint = sas_program.key() == SOLANA_ATTESTATION_SERVICE_ID @ ErrorCode::InvalidSasProgram
@@ -49,9 +96,13 @@ pub fn handler(
         require!(exp > clock.unix_timestamp, ErrorCode::ExpirationInPast);
     }
 
+    // Check data against the schema layout before paying for the CPI
+    crate::validation::validate_attestation_data(&ctx.accounts.schema.to_account_info(), &data)?;
+
     // Get account infos with proper lifetimes
     let sas_program_info = ctx.accounts.sas_program.to_account_info();
     let authority_info = ctx.accounts.authority.to_account_info();
+    let credential_authority_info = ctx.accounts.credential_authority.to_account_info();
     let credential_info = ctx.accounts.credential.to_account_info();
     let schema_info = ctx.accounts.schema.to_account_info();
     let attestation_info = ctx.accounts.attestation.to_account_info();
@@ -62,7 +113,7 @@ pub fn handler(
     
     builder
         .payer(&authority_info)
-        .authority(&authority_info)
+        .authority(&credential_authority_info)
         .credential(&credential_info)
         .schema(&schema_info)
         .attestation(&attestation_info)
@@ -75,7 +126,26 @@ pub fn handler(
         builder.expiry(exp);
     }
 
-    builder.invoke().map_err(|_| ErrorCode::SasCpiFailed)?;
+    let bump = [ctx.bumps.credential_authority];
+    let signer_seeds: &[&[u8]] = &[CREDENTIAL_AUTHORITY_PREFIX.as_bytes(), &bump];
+
+    builder.invoke_signed(&[signer_seeds]).map_err(|e| {
+        msg!("SAS CreateAttestation failed: {}", e);
+        ErrorCode::SasCpiFailed
+    })?;
+
+    // Consume the request and release its escrowed fee
+    let amount = ctx.accounts.request.amount;
+    if amount > 0 {
+        **ctx.accounts.request.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.fee_recipient.to_account_info().try_borrow_mut_lamports()? += amount;
+    }
+
+    emit!(AttestationRequestApproved {
+        request: ctx.accounts.request.key(),
+        attestation: ctx.accounts.attestation.key(),
+        amount,
+    });
 
     emit!(AttestationCreated {
         attestation: ctx.accounts.attestation.key(),
@@ -20,7 +20,14 @@ pub struct CreateCredential<'info> {
     )]
     pub config: Account<'info, AttestationConfig>,
 
-    /// CHECK: SAS Credential account - will be created by SAS program
+    /// CHECK: PDA that owns the SAS credential and schemas, signs SAS CPIs
+    #[account(
+        seeds = [CREDENTIAL_AUTHORITY_PREFIX.as_bytes()],
+        bump
+    )]
+    pub credential_authority: UncheckedAccount<'info>,
+
+    /// CHECK: SAS Credential account - will be created by SAS program, owned by credential_authority
     #[account(mut)]
     pub credential: UncheckedAccount<'info>,
 
@@ -33,28 +40,29 @@ pub struct CreateCredential<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<CreateCredential>,
-    name: String,
-    signers: Vec<Pubkey>,
-) -> Result<()> {
+pub fn handler(ctx: Context<CreateCredential>, name: String) -> Result<()> {
     require!(!name.is_empty(), ErrorCode::EmptySchemaName);
 
     // Get account infos with proper lifetimes
     let sas_program_info = ctx.accounts.sas_program.to_account_info();
     let authority_info = ctx.accounts.authority.to_account_info();
+    let credential_authority_info = ctx.accounts.credential_authority.to_account_info();
     let credential_info = ctx.accounts.credential.to_account_info();
     let system_program_info = ctx.accounts.system_program.to_account_info();
 
-    // CPI call to SAS to create credential
+    let bump = [ctx.bumps.credential_authority];
+    let signer_seeds: &[&[u8]] = &[CREDENTIAL_AUTHORITY_PREFIX.as_bytes(), &bump];
+
+    // CPI call to SAS to create credential - the PDA is both authority and sole signer,
+    // so operator access is decided by config alone
     CreateCredentialCpiBuilder::new(&sas_program_info)
         .payer(&authority_info)
         .credential(&credential_info)
-        .authority(&authority_info)
+        .authority(&credential_authority_info)
         .system_program(&system_program_info)
         .name(name.clone())
-        .signers(signers)
-        .invoke()
+        .signers(vec![ctx.accounts.credential_authority.key()])
+        .invoke_signed(&[signer_seeds])
         .map_err(|_| ErrorCode::SasCpiFailed)?;
 
     emit!(CredentialCreated {
@@ -3,6 +3,7 @@ use solana_attestation_service_client::{
     instructions::CreateSchemaCpiBuilder,
     programs::SOLANA_ATTESTATION_SERVICE_ID,
 };
+use crate::instructions::schema_registry::init_schema_record;
 use crate::state::*;
 use crate::error::ErrorCode;
 
@@ -20,6 +21,13 @@ pub struct CreateSchema<'info> {
     )]
     pub config: Account<'info, AttestationConfig>,
 
+    /// CHECK: PDA that owns the SAS credential and schemas, signs SAS CPIs
+    #[account(
+        seeds = [CREDENTIAL_AUTHORITY_PREFIX.as_bytes()],
+        bump
+    )]
+    pub credential_authority: UncheckedAccount<'info>,
+
     /// CHECK: SAS Credential account - required for schema creation
     pub credential: UncheckedAccount<'info>,
 
@@ -27,6 +35,16 @@ pub struct CreateSchema<'info> {
     #[account(mut)]
     pub schema: UncheckedAccount<'info>,
 
+    /// Registry entry for the new schema, filled from the SAS account after creation
+    #[account(
+        init,
+        payer = authority,
+        space = SCHEMA_RECORD_SIZE,
+        seeds = [SCHEMA_RECORD_PREFIX.as_bytes(), schema.key().as_ref()],
+        bump
+    )]
+    pub schema_record: Account<'info, SchemaRecord>,
+
     /// CHECK: SAS Program
     #[account(
         constraint = sas_program.key() == SOLANA_ATTESTATION_SERVICE_ID @ ErrorCode::InvalidSasProgram
@@ -44,23 +62,28 @@ pub fn handler(
     field_names: Vec<String>,
 ) -> Result<()> {
     require!(!name.is_empty(), ErrorCode::EmptySchemaName);
+    require!(name.len() <= MAX_SCHEMA_NAME_LEN, ErrorCode::SchemaNameTooLong);
 
     let config = &mut ctx.accounts.config;
     
     // Increment schema count
-    config.schema_count = config.schema_count.checked_add(1).unwrap();
+    config.schema_count = config.schema_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
 
     // Get account infos with proper lifetimes
     let sas_program_info = ctx.accounts.sas_program.to_account_info();
     let authority_info = ctx.accounts.authority.to_account_info();
+    let credential_authority_info = ctx.accounts.credential_authority.to_account_info();
     let credential_info = ctx.accounts.credential.to_account_info();
     let schema_info = ctx.accounts.schema.to_account_info();
     let system_program_info = ctx.accounts.system_program.to_account_info();
 
+    let bump = [ctx.bumps.credential_authority];
+    let signer_seeds: &[&[u8]] = &[CREDENTIAL_AUTHORITY_PREFIX.as_bytes(), &bump];
+
     // CPI call to SAS to create schema
     CreateSchemaCpiBuilder::new(&sas_program_info)
         .payer(&authority_info)
-        .authority(&authority_info)
+        .authority(&credential_authority_info)
         .credential(&credential_info)
         .schema(&schema_info)
         .system_program(&system_program_info)
@@ -68,9 +91,15 @@ pub fn handler(
         .description(description)
         .layout(layout)
         .field_names(field_names)
-        .invoke()
+        .invoke_signed(&[signer_seeds])
         .map_err(|_| ErrorCode::SasCpiFailed)?;
 
+    init_schema_record(
+        &mut ctx.accounts.schema_record,
+        &schema_info,
+        ctx.bumps.schema_record,
+    )?;
+
     emit!(SchemaCreated {
         schema: ctx.accounts.schema.key(),
         name: name.clone(),
@@ -33,6 +33,10 @@ pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
     config.attestation_fee = args.attestation_fee;
     config.fee_recipient = args.fee_recipient;
     config.bump = ctx.bumps.config;
+    config.pending_authority = None;
+    config.guardian = None;
+    config.paused = 0;
+    config.version = ATTESTATION_CONFIG_VERSION;
 
     emit!(ConfigInitialized {
         authority: config.authority,
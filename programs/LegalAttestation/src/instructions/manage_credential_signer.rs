@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use solana_attestation_service_client::{
+    accounts::Credential,
+    instructions::ChangeAuthorizedSignersCpiBuilder,
+    programs::SOLANA_ATTESTATION_SERVICE_ID,
+};
+use crate::instructions::manage_operator::{add_operator, remove_operator};
+use crate::state::*;
+use crate::error::ErrorCode;
+
+/// Change operators and the SAS credential's authorized signers together (admin only)
+/// The credential's signers are always the authority PDA followed by config.operators
+#[derive(Accounts)]
+pub struct ManageCredentialSigner<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+
+    /// CHECK: PDA that owns the SAS credential, signs the CPI
+    #[account(
+        seeds = [CREDENTIAL_AUTHORITY_PREFIX.as_bytes()],
+        bump
+    )]
+    pub credential_authority: UncheckedAccount<'info>,
+
+    /// CHECK: SAS Credential account - must be owned by credential_authority
+    #[account(mut, owner = SOLANA_ATTESTATION_SERVICE_ID @ ErrorCode::InvalidCredentialAccount)]
+    pub credential: UncheckedAccount<'info>,
+
+    /// CHECK: SAS Program
+    #[account(
+        constraint = sas_program.key() == SOLANA_ATTESTATION_SERVICE_ID @ ErrorCode::InvalidSasProgram
+    )]
+    pub sas_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_handler(ctx: Context<ManageCredentialSigner>, signer: Pubkey) -> Result<()> {
+    add_operator(&mut ctx.accounts.config, signer)?;
+
+    let signers = sync_credential_signers(&ctx)?;
+
+    emit!(CredentialSignerAdded {
+        credential: ctx.accounts.credential.key(),
+        signer,
+        signers,
+    });
+
+    msg!(
+        "Credential signer added: signer={}, credential={}",
+        signer,
+        ctx.accounts.credential.key()
+    );
+
+    Ok(())
+}
+
+pub fn remove_handler(ctx: Context<ManageCredentialSigner>, signer: Pubkey) -> Result<()> {
+    remove_operator(&mut ctx.accounts.config, signer)?;
+
+    let signers = sync_credential_signers(&ctx)?;
+
+    emit!(CredentialSignerRemoved {
+        credential: ctx.accounts.credential.key(),
+        signer,
+        signers,
+    });
+
+    msg!(
+        "Credential signer removed: signer={}, credential={}",
+        signer,
+        ctx.accounts.credential.key()
+    );
+
+    Ok(())
+}
+
+/// Overwrite the credential's signers with the PDA plus current operators,
+/// so any drift from earlier add_operator / remove_operator calls is fixed too
+fn sync_credential_signers(ctx: &Context<ManageCredentialSigner>) -> Result<Vec<Pubkey>> {
+    let credential = {
+        let data = ctx.accounts.credential.try_borrow_data()?;
+        Credential::from_bytes(&data).map_err(|_| ErrorCode::InvalidCredentialAccount)?
+    };
+
+    require!(
+        credential.authority == ctx.accounts.credential_authority.key(),
+        ErrorCode::InvalidCredentialAccount
+    );
+
+    let mut signers = vec![ctx.accounts.credential_authority.key()];
+    signers.extend(ctx.accounts.config.operators.iter().copied());
+
+    // Get account infos with proper lifetimes
+    let sas_program_info = ctx.accounts.sas_program.to_account_info();
+    let authority_info = ctx.accounts.authority.to_account_info();
+    let credential_authority_info = ctx.accounts.credential_authority.to_account_info();
+    let credential_info = ctx.accounts.credential.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let bump = [ctx.bumps.credential_authority];
+    let signer_seeds: &[&[u8]] = &[CREDENTIAL_AUTHORITY_PREFIX.as_bytes(), &bump];
+
+    // CPI call to SAS to replace the credential's authorized signers
+    ChangeAuthorizedSignersCpiBuilder::new(&sas_program_info)
+        .payer(&authority_info)
+        .authority(&credential_authority_info)
+        .credential(&credential_info)
+        .system_program(&system_program_info)
+        .signers(signers.clone())
+        .invoke_signed(&[signer_seeds])
+        .map_err(|e| {
+            msg!("SAS ChangeAuthorizedSigners failed: {}", e);
+            ErrorCode::SasCpiFailed
+        })?;
+
+    Ok(signers)
+}
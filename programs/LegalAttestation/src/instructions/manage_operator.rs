@@ -17,8 +17,23 @@ pub struct ManageOperator<'info> {
 }
 
 pub fn add_handler(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
-    let config = &mut ctx.accounts.config;
+    add_operator(&mut ctx.accounts.config, operator)?;
 
+    msg!("Operator added: {}", operator);
+
+    Ok(())
+}
+
+pub fn remove_handler(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+    remove_operator(&mut ctx.accounts.config, operator)?;
+
+    msg!("Operator removed: {}", operator);
+
+    Ok(())
+}
+
+/// Append an operator, shared with add_credential_signer
+pub(crate) fn add_operator(config: &mut AttestationConfig, operator: Pubkey) -> Result<()> {
     require!(
         config.operators.len() < MAX_OPERATORS,
         ErrorCode::MaxOperatorsReached
@@ -33,14 +48,11 @@ pub fn add_handler(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()>
 
     emit!(OperatorAdded { operator });
 
-    msg!("Operator added: {}", operator);
-
     Ok(())
 }
 
-pub fn remove_handler(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
-    let config = &mut ctx.accounts.config;
-
+/// Drop an operator, shared with remove_credential_signer
+pub(crate) fn remove_operator(config: &mut AttestationConfig, operator: Pubkey) -> Result<()> {
     let index = config
         .operators
         .iter()
@@ -51,7 +63,5 @@ pub fn remove_handler(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<
 
     emit!(OperatorRemoved { operator });
 
-    msg!("Operator removed: {}", operator);
-
     Ok(())
 }
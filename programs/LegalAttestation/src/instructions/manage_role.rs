@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ROLE_ACCOUNT_SIZE,
+        seeds = [ROLE_PREFIX.as_bytes(), holder.as_ref()],
+        bump
+    )]
+    pub role: Account<'info, RoleAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(holder: Pubkey)]
+pub struct RevokeRole<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [ROLE_PREFIX.as_bytes(), holder.as_ref()],
+        bump = role.bump
+    )]
+    pub role: Account<'info, RoleAccount>,
+}
+
+pub fn grant_handler(ctx: Context<GrantRole>, holder: Pubkey, permissions: u32) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !permissions::ALL == 0,
+        ErrorCode::InvalidPermissions
+    );
+
+    let role = &mut ctx.accounts.role;
+
+    role.holder = holder;
+    role.permissions = permissions;
+    role.bump = ctx.bumps.role;
+
+    emit!(RoleGranted {
+        holder,
+        permissions,
+    });
+
+    msg!("Role granted: holder={}, permissions={}", holder, permissions);
+
+    Ok(())
+}
+
+pub fn revoke_handler(_ctx: Context<RevokeRole>, holder: Pubkey) -> Result<()> {
+    emit!(RoleRevoked { holder });
+
+    msg!("Role revoked: holder={}", holder);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use crate::state::*;
+use crate::error::ErrorCode;
+
+/// Upgrade the AttestationConfig in place to the current layout (admin only)
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Deserialized by hand - an older layout can't load as Account<AttestationConfig>
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+
+    // Accounts allocated before `version` existed are shorter than the current size
+    let (mut config, from_version) = {
+        let data = config_info.try_borrow_data()?;
+        require!(
+            data.starts_with(AttestationConfig::DISCRIMINATOR),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        if data.len() < ATTESTATION_CONFIG_SIZE {
+            let old = AttestationConfigV0::deserialize(&mut &data[8..])
+                .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+            (AttestationConfig::from(old), 0)
+        } else {
+            let config = AttestationConfig::try_deserialize(&mut &data[..])?;
+            let version = config.version;
+            (config, version)
+        }
+    };
+
+    require!(
+        config.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(
+        from_version < ATTESTATION_CONFIG_VERSION,
+        ErrorCode::AlreadyMigrated
+    );
+
+    // Defaults for fields added since `from_version`
+    config.version = ATTESTATION_CONFIG_VERSION;
+
+    if config_info.data_len() < ATTESTATION_CONFIG_SIZE {
+        let rent = Rent::get()?;
+        let top_up = rent
+            .minimum_balance(ATTESTATION_CONFIG_SIZE)
+            .saturating_sub(config_info.lamports());
+
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: config_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+
+        config_info.resize(ATTESTATION_CONFIG_SIZE)?;
+    }
+
+    // Rewrite the whole account so no bytes from the old layout are left behind
+    let mut data = config_info.try_borrow_mut_data()?;
+    data.fill(0);
+    let mut writer: &mut [u8] = &mut data;
+    config.try_serialize(&mut writer)?;
+
+    emit!(ConfigMigrated {
+        from_version,
+        to_version: ATTESTATION_CONFIG_VERSION,
+    });
+
+    msg!(
+        "Config migrated: version {} -> {}",
+        from_version,
+        ATTESTATION_CONFIG_VERSION
+    );
+
+    Ok(())
+}
@@ -1,17 +1,33 @@
 #![allow(ambiguous_glob_reexports)]
 
 pub mod initialize;
+pub mod migrate_config;
 pub mod manage_operator;
+pub mod manage_role;
+pub mod transfer_authority;
+pub mod pause;
 pub mod create_credential;
+pub mod manage_credential_signer;
 pub mod create_schema;
+pub mod schema_registry;
 pub mod request_attestation;
+pub mod reject_attestation_request;
+pub mod refund_attestation_request;
 pub mod create_attestation;
 pub mod revoke_attestation;
 
 pub use initialize::*;
+pub use migrate_config::*;
 pub use manage_operator::*;
+pub use manage_role::*;
+pub use transfer_authority::*;
+pub use pause::*;
 pub use create_credential::*;
+pub use manage_credential_signer::*;
 pub use create_schema::*;
+pub use schema_registry::*;
 pub use request_attestation::*;
+pub use reject_attestation_request::*;
+pub use refund_attestation_request::*;
 pub use create_attestation::*;
 pub use revoke_attestation::*;
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ErrorCode;
+
+/// Set or clear the guardian key (admin only)
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+}
+
+/// Change pause flags (admin, or guardian for pausing only)
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.is_admin_or_guardian(&authority.key()) @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+}
+
+pub fn set_guardian_handler(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+    ctx.accounts.config.guardian = guardian;
+
+    emit!(GuardianSet { guardian });
+
+    msg!("Guardian set: {:?}", guardian);
+
+    Ok(())
+}
+
+pub fn set_paused_handler(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    require!(paused & !pause_flags::ALL == 0, ErrorCode::InvalidPauseFlags);
+
+    // A leaked guardian key must not be able to undo an admin pause
+    if !config.is_admin(&ctx.accounts.authority.key()) {
+        require!(
+            paused & config.paused == config.paused,
+            ErrorCode::InvalidPauseFlags
+        );
+    }
+
+    config.paused = paused;
+
+    emit!(PauseChanged {
+        paused,
+        changed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Pause flags set to: {} by {}", paused, ctx.accounts.authority.key());
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ErrorCode;
+
+/// User refunds their own request once nobody approved or rejected it in time
+/// Closing it returns the escrowed fee and rent, not gated by pause
+#[derive(Accounts)]
+pub struct RefundAttestationRequest<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [ATTESTATION_REQUEST_PREFIX.as_bytes(), user.key().as_ref(), &request_id_seed(&request.request_id)],
+        bump = request.bump,
+        constraint = request.user == user.key() @ ErrorCode::RequestUserMismatch
+    )]
+    pub request: Account<'info, AttestationRequest>,
+}
+
+pub fn handler(ctx: Context<RefundAttestationRequest>) -> Result<()> {
+    let request = &ctx.accounts.request;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= request.created_at.saturating_add(ATTESTATION_REQUEST_TIMEOUT_SECONDS),
+        ErrorCode::RequestNotExpired
+    );
+
+    emit!(AttestationRequestRefunded {
+        request: request.key(),
+        user: request.user,
+        request_id: request.request_id.clone(),
+        refunded: request.amount,
+    });
+
+    msg!(
+        "Attestation request refunded: request={}, user={}, refunded={} lamports",
+        request.key(),
+        request.user,
+        request.amount
+    );
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ErrorCode;
+
+/// Reject a pending request, closing it refunds the escrowed fee and rent to the user
+/// Not gated by pause, so users can always get their money back
+#[derive(Accounts)]
+pub struct RejectAttestationRequest<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.has_permission(&authority.key(), role.as_deref(), permissions::ATTEST) @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+
+    /// Role granting the signer permission (not needed for admin / operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [ATTESTATION_REQUEST_PREFIX.as_bytes(), request.user.as_ref(), &request_id_seed(&request.request_id)],
+        bump = request.bump
+    )]
+    pub request: Account<'info, AttestationRequest>,
+
+    /// User who paid the request, receives the refund
+    #[account(
+        mut,
+        constraint = user.key() == request.user @ ErrorCode::RequestUserMismatch
+    )]
+    pub user: SystemAccount<'info>,
+}
+
+pub fn handler(ctx: Context<RejectAttestationRequest>) -> Result<()> {
+    let amount = ctx.accounts.request.amount;
+    let request = &ctx.accounts.request;
+
+    emit!(AttestationRequestRejected {
+        request: request.key(),
+        user: request.user,
+        request_id: request.request_id.clone(),
+        refunded: amount,
+    });
+
+    msg!(
+        "Attestation request rejected: request={}, user={}, refunded={} lamports",
+        request.key(),
+        request.user,
+        amount
+    );
+
+    Ok(())
+}
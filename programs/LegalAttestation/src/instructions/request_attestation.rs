@@ -5,24 +5,35 @@ use crate::error::ErrorCode;
 #[derive(Accounts)]
 #[instruction(schema: Pubkey, request_id: String)]
 pub struct RequestAttestation<'info> {
-    /// User requesting the attestation (pays fee)
+    /// User requesting the attestation (pays fee and request rent)
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// Attestation config (reads fee and fee_recipient)
+    /// Attestation config (reads default fee)
     #[account(
         seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
-        bump = config.bump
+        bump = config.bump,
+        constraint = !config.is_paused(pause_flags::USER) @ ErrorCode::ProgramPaused
     )]
     pub config: Account<'info, AttestationConfig>,
 
-    /// Fee recipient address
-    /// CHECK: Validated from config account
+    /// Registry entry of the requested schema - unknown schemas have none
     #[account(
-        mut,
-        constraint = fee_recipient.key() == config.fee_recipient @ ErrorCode::InvalidFeeRecipient
+        seeds = [SCHEMA_RECORD_PREFIX.as_bytes(), schema.as_ref()],
+        bump = schema_record.bump,
+        constraint = schema_record.active @ ErrorCode::SchemaInactive
     )]
-    pub fee_recipient: UncheckedAccount<'info>,
+    pub schema_record: Account<'info, SchemaRecord>,
+
+    /// Request record, escrows the fee - `init` rejects a request_id the user already paid
+    #[account(
+        init,
+        payer = user,
+        space = ATTESTATION_REQUEST_SIZE,
+        seeds = [ATTESTATION_REQUEST_PREFIX.as_bytes(), user.key().as_ref(), &request_id_seed(&request_id)],
+        bump
+    )]
+    pub request: Account<'info, AttestationRequest>,
 
     pub system_program: Program<'info, System>,
 }
@@ -32,30 +43,47 @@ pub fn handler(
     schema: Pubkey,
     request_id: String,
 ) -> Result<()> {
-    let config = &ctx.accounts.config;
-    let attestation_fee = config.attestation_fee;
+    require!(
+        !request_id.is_empty() && request_id.len() <= MAX_REQUEST_ID_LEN,
+        ErrorCode::InvalidRequestId
+    );
+
+    let attestation_fee = ctx
+        .accounts
+        .schema_record
+        .fee
+        .unwrap_or(ctx.accounts.config.attestation_fee);
 
-    // Transfer fee to fee_recipient
+    // Escrow fee in the request account until it is approved, rejected or refunded
     if attestation_fee > 0 {
         let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
             &ctx.accounts.user.key(),
-            &ctx.accounts.fee_recipient.key(),
+            &ctx.accounts.request.key(),
             attestation_fee,
         );
         anchor_lang::solana_program::program::invoke(
             &transfer_ix,
             &[
                 ctx.accounts.user.to_account_info(),
-                ctx.accounts.fee_recipient.to_account_info(),
+                ctx.accounts.request.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
         )?;
     }
 
-    msg!("Attestation requested (payment received)");
+    let request = &mut ctx.accounts.request;
+    request.user = ctx.accounts.user.key();
+    request.schema = schema;
+    request.request_id = request_id.clone();
+    request.amount = attestation_fee;
+    request.created_at = Clock::get()?.unix_timestamp;
+    request.bump = ctx.bumps.request;
+
+    msg!("Attestation requested (payment escrowed)");
     msg!("User: {}", ctx.accounts.user.key());
     msg!("Schema: {}", schema);
     msg!("Request ID: {}", request_id);
+    msg!("Request: {}", ctx.accounts.request.key());
     msg!("Amount: {} lamports", attestation_fee);
 
     emit!(AttestationRequested {
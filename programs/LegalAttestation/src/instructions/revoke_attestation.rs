@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use solana_attestation_service_client::{
+    accounts::Credential,
     instructions::CloseAttestationCpiBuilder,
     programs::SOLANA_ATTESTATION_SERVICE_ID,
 };
@@ -14,11 +15,23 @@ pub struct RevokeAttestation<'info> {
     #[account(
         seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
         bump = config.bump,
-        constraint = config.is_admin_or_operator(&authority.key()) @ ErrorCode::Unauthorized
+        constraint = config.has_permission(&authority.key(), role.as_deref(), permissions::REVOKE) @ ErrorCode::Unauthorized,
+        constraint = !config.is_paused(pause_flags::OPERATOR) @ ErrorCode::ProgramPaused
     )]
     pub config: Account<'info, AttestationConfig>,
 
-    /// CHECK: SAS Credential account
+    /// Role granting the signer REVOKE (not needed for admin)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    /// CHECK: PDA that owns the SAS credential and schemas, signs SAS CPIs
+    #[account(
+        seeds = [CREDENTIAL_AUTHORITY_PREFIX.as_bytes()],
+        bump
+    )]
+    pub credential_authority: UncheckedAccount<'info>,
+
+    /// CHECK: SAS Credential account - its authority picks the signing path
+    #[account(owner = SOLANA_ATTESTATION_SERVICE_ID @ ErrorCode::InvalidCredentialAccount)]
     pub credential: UncheckedAccount<'info>,
 
     /// CHECK: SAS Attestation account to close/revoke
@@ -41,21 +54,45 @@ pub fn handler(ctx: Context<RevokeAttestation>) -> Result<()> {
     // Get account infos with proper lifetimes
     let sas_program_info = ctx.accounts.sas_program.to_account_info();
     let authority_info = ctx.accounts.authority.to_account_info();
+    let credential_authority_info = ctx.accounts.credential_authority.to_account_info();
     let credential_info = ctx.accounts.credential.to_account_info();
     let attestation_info = ctx.accounts.attestation.to_account_info();
     let event_authority_info = ctx.accounts.event_authority.to_account_info();
     let system_program_info = ctx.accounts.system_program.to_account_info();
 
+    let credential = {
+        let data = credential_info.try_borrow_data()?;
+        Credential::from_bytes(&data).map_err(|_| ErrorCode::InvalidCredentialAccount)?
+    };
+
+    let bump = [ctx.bumps.credential_authority];
+    let signer_seeds: &[&[u8]] = &[CREDENTIAL_AUTHORITY_PREFIX.as_bytes(), &bump];
+
+    // Credentials created before the authority PDA are owned by the admin and list the
+    // admin / operators as signers, their attestations are closed by the caller directly
+    let legacy_credential = credential.authority != ctx.accounts.credential_authority.key();
+    if legacy_credential {
+        require!(
+            credential.authorized_signers.contains(&ctx.accounts.authority.key()),
+            ErrorCode::Unauthorized
+        );
+    }
+    let sas_authority_info = if legacy_credential {
+        &authority_info
+    } else {
+        &credential_authority_info
+    };
+
     // CPI call to SAS to close/revoke attestation
     CloseAttestationCpiBuilder::new(&sas_program_info)
         .payer(&authority_info)
-        .authority(&authority_info)
+        .authority(sas_authority_info)
         .credential(&credential_info)
         .attestation(&attestation_info)
         .event_authority(&event_authority_info)
         .system_program(&system_program_info)
         .attestation_program(&sas_program_info)
-        .invoke()
+        .invoke_signed(&[signer_seeds])
         .map_err(|_| ErrorCode::SasCpiFailed)?;
 
     emit!(AttestationRevoked {
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use solana_attestation_service_client::{
+    accounts::Schema,
+    programs::SOLANA_ATTESTATION_SERVICE_ID,
+};
+use crate::state::*;
+use crate::error::ErrorCode;
+
+/// Register an existing SAS schema (created before the registry) (admin only)
+#[derive(Accounts)]
+pub struct RegisterSchema<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+
+    /// CHECK: SAS Schema account - owner checked, layout parsed in handler
+    #[account(owner = SOLANA_ATTESTATION_SERVICE_ID @ ErrorCode::InvalidSchemaAccount)]
+    pub schema: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = SCHEMA_RECORD_SIZE,
+        seeds = [SCHEMA_RECORD_PREFIX.as_bytes(), schema.key().as_ref()],
+        bump
+    )]
+    pub schema_record: Account<'info, SchemaRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pause / resume a schema, or change its fee override (admin only)
+#[derive(Accounts)]
+pub struct UpdateSchemaRecord<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+
+    #[account(
+        mut,
+        seeds = [SCHEMA_RECORD_PREFIX.as_bytes(), schema_record.schema.as_ref()],
+        bump = schema_record.bump
+    )]
+    pub schema_record: Account<'info, SchemaRecord>,
+}
+
+/// Fill a new SchemaRecord from the SAS schema account, active with no fee override
+pub fn init_schema_record(
+    record: &mut SchemaRecord,
+    schema_info: &AccountInfo,
+    bump: u8,
+) -> Result<()> {
+    let schema = {
+        let data = schema_info.try_borrow_data()?;
+        Schema::from_bytes(&data).map_err(|_| ErrorCode::InvalidSchemaAccount)?
+    };
+
+    let name = String::from_utf8(schema.name).map_err(|_| ErrorCode::InvalidSchemaAccount)?;
+    require!(name.len() <= MAX_SCHEMA_NAME_LEN, ErrorCode::SchemaNameTooLong);
+
+    record.schema = schema_info.key();
+    record.name = name;
+    record.layout_hash = hash(&schema.layout).to_bytes();
+    record.fee = None;
+    record.active = true;
+    record.version = schema.version;
+    record.bump = bump;
+
+    emit!(SchemaRegistered {
+        schema: record.schema,
+        name: record.name.clone(),
+        layout_hash: record.layout_hash,
+        version: record.version,
+    });
+
+    Ok(())
+}
+
+pub fn register_handler(ctx: Context<RegisterSchema>) -> Result<()> {
+    init_schema_record(
+        &mut ctx.accounts.schema_record,
+        &ctx.accounts.schema.to_account_info(),
+        ctx.bumps.schema_record,
+    )?;
+
+    msg!(
+        "Schema registered: name={}, schema={}",
+        ctx.accounts.schema_record.name,
+        ctx.accounts.schema.key()
+    );
+
+    Ok(())
+}
+
+pub fn pause_handler(ctx: Context<UpdateSchemaRecord>) -> Result<()> {
+    let record = &mut ctx.accounts.schema_record;
+    require!(record.active, ErrorCode::SchemaInactive);
+    record.active = false;
+
+    emit!(SchemaStatusChanged {
+        schema: record.schema,
+        active: false,
+    });
+
+    msg!("Schema paused: {}", record.schema);
+
+    Ok(())
+}
+
+pub fn resume_handler(ctx: Context<UpdateSchemaRecord>) -> Result<()> {
+    let record = &mut ctx.accounts.schema_record;
+    require!(!record.active, ErrorCode::SchemaAlreadyActive);
+    record.active = true;
+
+    emit!(SchemaStatusChanged {
+        schema: record.schema,
+        active: true,
+    });
+
+    msg!("Schema resumed: {}", record.schema);
+
+    Ok(())
+}
+
+pub fn set_fee_handler(ctx: Context<UpdateSchemaRecord>, fee: Option<u64>) -> Result<()> {
+    let record = &mut ctx.accounts.schema_record;
+    record.fee = fee;
+
+    emit!(SchemaFeeSet {
+        schema: record.schema,
+        fee,
+    });
+
+    msg!("Schema fee set: schema={}, fee={:?}", record.schema, fee);
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ErrorCode;
+
+/// Propose or cancel an authority transfer (admin only)
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, AttestationConfig>,
+}
+
+/// Accept a pending authority transfer (pending authority only)
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ATTESTATION_CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.pending_authority.is_some() @ ErrorCode::NoPendingAuthority,
+        constraint = config.pending_authority == Some(new_authority.key()) @ ErrorCode::InvalidPendingAuthority
+    )]
+    pub config: Account<'info, AttestationConfig>,
+}
+
+pub fn propose_handler(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.pending_authority = Some(new_authority);
+
+    emit!(AuthorityProposed {
+        authority: config.authority,
+        pending_authority: new_authority,
+    });
+
+    msg!("Authority transfer proposed: {} -> {}", config.authority, new_authority);
+
+    Ok(())
+}
+
+pub fn accept_handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let old_authority = config.authority;
+    config.authority = ctx.accounts.new_authority.key();
+    config.pending_authority = None;
+
+    emit!(AuthorityTransferred {
+        old_authority,
+        new_authority: config.authority,
+    });
+
+    msg!("Authority transferred: {} -> {}", old_authority, config.authority);
+
+    Ok(())
+}
+
+pub fn cancel_handler(ctx: Context<ProposeAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let pending_authority = config
+        .pending_authority
+        .take()
+        .ok_or(ErrorCode::NoPendingAuthority)?;
+
+    emit!(AuthorityTransferCancelled {
+        authority: config.authority,
+        pending_authority,
+    });
+
+    msg!("Authority transfer cancelled: {}", pending_authority);
+
+    Ok(())
+}
@@ -3,87 +3,174 @@ use anchor_lang::prelude::*;
 pub mod error;
 pub mod instructions;
 pub mod state;
+pub mod validation;
 
 use instructions::*;
 use state::*;
 
 declare_id!("4L4PvfugSGXuosyZSQxGxL5B9WqhUVqMEfwqMEUdUGiW");
 
-#[program]
-pub mod legal_attestation {
+// The IDL instruction handlers generated by #[program] still call the deprecated AccountInfo::realloc.
+// They're emitted next to the program module rather than inside it, so the allow sits on this wrapper
+#[allow(deprecated)]
+mod program_module {
     use super::*;
 
-    /// Initialize the attestation program
-    pub fn initialize(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
-        instructions::initialize::handler(ctx, args)
-    }
+    #[program]
+    pub mod legal_attestation {
+        use super::*;
 
-    /// Set attestation fee (admin only)
-    pub fn set_attestation_fee(ctx: Context<SetAttestationFee>, fee: u64) -> Result<()> {
-        ctx.accounts.config.attestation_fee = fee;
-        msg!("Attestation fee set to: {} lamports", fee);
-        Ok(())
-    }
+        /// Initialize the attestation program
+        pub fn initialize(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
+            instructions::initialize::handler(ctx, args)
+        }
 
-    /// Set fee recipient (admin only)
-    pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>, recipient: Pubkey) -> Result<()> {
-        ctx.accounts.config.fee_recipient = recipient;
-        msg!("Fee recipient set to: {}", recipient);
-        Ok(())
-    }
+        /// Upgrade the config account to the current layout in place (admin only)
+        pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+            instructions::migrate_config::handler(ctx)
+        }
 
-    /// Add an operator (admin only)
-    pub fn add_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
-        instructions::manage_operator::add_handler(ctx, operator)
-    }
+        /// Set attestation fee (admin only)
+        pub fn set_attestation_fee(ctx: Context<SetAttestationFee>, fee: u64) -> Result<()> {
+            ctx.accounts.config.attestation_fee = fee;
+            msg!("Attestation fee set to: {} lamports", fee);
+            Ok(())
+        }
 
-    /// Remove an operator (admin only)
-    pub fn remove_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
-        instructions::manage_operator::remove_handler(ctx, operator)
-    }
+        /// Set fee recipient (admin only)
+        pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>, recipient: Pubkey) -> Result<()> {
+            ctx.accounts.config.fee_recipient = recipient;
+            msg!("Fee recipient set to: {}", recipient);
+            Ok(())
+        }
 
-    /// Create a credential via SAS (admin only) - must be called first before creating schemas
-    pub fn create_credential(
-        ctx: Context<CreateCredential>,
-        name: String,
-        signers: Vec<Pubkey>,
-    ) -> Result<()> {
-        instructions::create_credential::handler(ctx, name, signers)
-    }
+        /// Add an operator (admin only)
+        pub fn add_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+            instructions::manage_operator::add_handler(ctx, operator)
+        }
 
-    /// Create a schema via SAS (admin only)
-    pub fn create_schema(
-        ctx: Context<CreateSchema>,
-        name: String,
-        description: String,
-        layout: Vec<u8>,
-        field_names: Vec<String>,
-    ) -> Result<()> {
-        instructions::create_schema::handler(ctx, name, description, layout, field_names)
-    }
+        /// Remove an operator (admin only)
+        pub fn remove_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+            instructions::manage_operator::remove_handler(ctx, operator)
+        }
 
-    /// User requests an attestation (pays fee)
-    /// Backend listens to events and approves via create_attestation
-    pub fn request_attestation(
-        ctx: Context<RequestAttestation>,
-        schema: Pubkey,
-        request_id: String,
-    ) -> Result<()> {
-        instructions::request_attestation::handler(ctx, schema, request_id)
-    }
+        /// Grant or update a role's permission bitflags (admin only)
+        pub fn grant_role(ctx: Context<GrantRole>, holder: Pubkey, permissions: u32) -> Result<()> {
+            instructions::manage_role::grant_handler(ctx, holder, permissions)
+        }
 
-    /// Create an attestation via SAS (admin/operator) - called after approving request
-    pub fn create_attestation(
-        ctx: Context<CreateAttestation>,
-        nonce: Pubkey,
-        data: Vec<u8>,
-        expiry: Option<i64>,
-    ) -> Result<()> {
-        instructions::create_attestation::handler(ctx, nonce, data, expiry)
-    }
+        /// Remove a role holder (admin only)
+        pub fn revoke_role(ctx: Context<RevokeRole>, holder: Pubkey) -> Result<()> {
+            instructions::manage_role::revoke_handler(ctx, holder)
+        }
+
+        /// Propose a new authority (admin only), takes effect after accept_authority
+        pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+            instructions::transfer_authority::propose_handler(ctx, new_authority)
+        }
+
+        /// Pending authority accepts the transfer
+        pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+            instructions::transfer_authority::accept_handler(ctx)
+        }
+
+        /// Cancel a pending authority transfer (admin only)
+        pub fn cancel_authority_transfer(ctx: Context<ProposeAuthority>) -> Result<()> {
+            instructions::transfer_authority::cancel_handler(ctx)
+        }
+
+        /// Set or clear the guardian key that can pause the program (admin only)
+        pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+            instructions::pause::set_guardian_handler(ctx, guardian)
+        }
+
+        /// Set pause flags (admin, or guardian which can only add flags)
+        pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+            instructions::pause::set_paused_handler(ctx, paused)
+        }
 
-    /// Revoke an attestation via SAS (admin/operator)
-    pub fn revoke_attestation(ctx: Context<RevokeAttestation>) -> Result<()> {
-        instructions::revoke_attestation::handler(ctx)
+        /// Create a credential via SAS owned by the program's credential authority PDA (admin only)
+        /// Must be called first before creating schemas
+        pub fn create_credential(ctx: Context<CreateCredential>, name: String) -> Result<()> {
+            instructions::create_credential::handler(ctx, name)
+        }
+
+        /// Add an operator and make it a signer of the SAS credential (admin only)
+        pub fn add_credential_signer(ctx: Context<ManageCredentialSigner>, signer: Pubkey) -> Result<()> {
+            instructions::manage_credential_signer::add_handler(ctx, signer)
+        }
+
+        /// Remove an operator and drop it from the SAS credential signers (admin only)
+        pub fn remove_credential_signer(ctx: Context<ManageCredentialSigner>, signer: Pubkey) -> Result<()> {
+            instructions::manage_credential_signer::remove_handler(ctx, signer)
+        }
+
+        /// Create a schema via SAS and register it (admin only)
+        pub fn create_schema(
+            ctx: Context<CreateSchema>,
+            name: String,
+            description: String,
+            layout: Vec<u8>,
+            field_names: Vec<String>,
+        ) -> Result<()> {
+            instructions::create_schema::handler(ctx, name, description, layout, field_names)
+        }
+
+        /// Register a SAS schema created outside create_schema (admin only)
+        pub fn register_schema(ctx: Context<RegisterSchema>) -> Result<()> {
+            instructions::schema_registry::register_handler(ctx)
+        }
+
+        /// Stop accepting requests and attestations for a schema (admin only)
+        pub fn pause_schema(ctx: Context<UpdateSchemaRecord>) -> Result<()> {
+            instructions::schema_registry::pause_handler(ctx)
+        }
+
+        /// Accept requests and attestations for a paused schema again (admin only)
+        pub fn resume_schema(ctx: Context<UpdateSchemaRecord>) -> Result<()> {
+            instructions::schema_registry::resume_handler(ctx)
+        }
+
+        /// Set or clear a schema's fee override (admin only)
+        pub fn set_schema_fee(ctx: Context<UpdateSchemaRecord>, fee: Option<u64>) -> Result<()> {
+            instructions::schema_registry::set_fee_handler(ctx, fee)
+        }
+
+        /// User requests an attestation, the fee is escrowed in a request PDA
+        /// Backend listens to events and approves via create_attestation or refunds via reject_attestation_request
+        pub fn request_attestation(
+            ctx: Context<RequestAttestation>,
+            schema: Pubkey,
+            request_id: String,
+        ) -> Result<()> {
+            instructions::request_attestation::handler(ctx, schema, request_id)
+        }
+
+        /// Create an attestation via SAS (admin/operator/ATTEST role) - consumes the user's pending request
+        pub fn create_attestation(
+            ctx: Context<CreateAttestation>,
+            nonce: Pubkey,
+            data: Vec<u8>,
+            expiry: Option<i64>,
+        ) -> Result<()> {
+            instructions::create_attestation::handler(ctx, nonce, data, expiry)
+        }
+
+        /// Reject a pending request and refund the user (admin/operator/ATTEST role)
+        pub fn reject_attestation_request(ctx: Context<RejectAttestationRequest>) -> Result<()> {
+            instructions::reject_attestation_request::handler(ctx)
+        }
+
+        /// Refund a request nobody approved or rejected within ATTESTATION_REQUEST_TIMEOUT_SECONDS (request's user)
+        pub fn refund_attestation_request(ctx: Context<RefundAttestationRequest>) -> Result<()> {
+            instructions::refund_attestation_request::handler(ctx)
+        }
+
+        /// Revoke an attestation via SAS (admin/operator/REVOKE role)
+        pub fn revoke_attestation(ctx: Context<RevokeAttestation>) -> Result<()> {
+            instructions::revoke_attestation::handler(ctx)
+        }
     }
 }
+
+pub use program_module::*;
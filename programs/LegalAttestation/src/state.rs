@@ -7,9 +7,56 @@ use anchor_lang::prelude::*;
 /// PDA seed for AttestationConfig
 pub const ATTESTATION_CONFIG_PREFIX: &str = "attestation-config";
 
+/// PDA seed for RoleAccount
+pub const ROLE_PREFIX: &str = "attestation-role";
+
+/// PDA seed for AttestationRequest
+pub const ATTESTATION_REQUEST_PREFIX: &str = "attestation-request";
+
+/// PDA seed for SchemaRecord
+pub const SCHEMA_RECORD_PREFIX: &str = "attestation-schema";
+
+/// PDA seed for the SAS credential authority, signs every SAS CPI
+pub const CREDENTIAL_AUTHORITY_PREFIX: &str = "attestation-authority";
+
+/// Role permission bitflags
+pub mod permissions {
+    /// Create attestations
+    pub const ATTEST: u32 = 1 << 0;
+    /// Revoke attestations
+    pub const REVOKE: u32 = 1 << 1;
+
+    pub const ALL: u32 = ATTEST | REVOKE;
+
+    /// Held by every operator without a role, revoking needs a REVOKE role
+    pub const OPERATOR_DEFAULT: u32 = ATTEST;
+}
+
+/// Pause bitflags, user-facing and operator-facing paths pause independently
+pub mod pause_flags {
+    /// request_attestation
+    pub const USER: u8 = 1 << 0;
+    /// create_attestation / revoke_attestation
+    pub const OPERATOR: u8 = 1 << 1;
+
+    pub const ALL: u8 = USER | OPERATOR;
+}
+
 /// Maximum number of operators
 pub const MAX_OPERATORS: usize = 5;
 
+/// Maximum request_id length
+pub const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// Seconds before a user can refund a request nobody approved or rejected (7 days)
+pub const ATTESTATION_REQUEST_TIMEOUT_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+/// Maximum schema name length kept in SchemaRecord
+pub const MAX_SCHEMA_NAME_LEN: usize = 64;
+
+/// Current AttestationConfig layout version, bump when appending fields and teach migrate_config the old layout
+pub const ATTESTATION_CONFIG_VERSION: u8 = 1;
+
 /// AttestationConfig account size
 pub const ATTESTATION_CONFIG_SIZE: usize = 8 +   // discriminator
     32 +                                          // authority
@@ -17,8 +64,42 @@ pub const ATTESTATION_CONFIG_SIZE: usize = 8 +   // discriminator
     8 +                                           // schema_count
     8 +                                           // attestation_fee
     32 +                                          // fee_recipient
+    1 +                                           // bump
+    1 + 32 +                                      // pending_authority (Option<Pubkey>)
+    1 + 32 +                                      // guardian (Option<Pubkey>)
+    1 +                                           // paused
+    1;                                            // version
+
+/// RoleAccount size
+pub const ROLE_ACCOUNT_SIZE: usize = 8 +  // discriminator
+    32 +                                    // holder
+    4 +                                     // permissions
+    1;                                      // bump
+
+/// AttestationRequest account size
+pub const ATTESTATION_REQUEST_SIZE: usize = 8 +  // discriminator
+    32 +                                          // user
+    32 +                                          // schema
+    4 + MAX_REQUEST_ID_LEN +                      // request_id
+    8 +                                           // amount
+    8 +                                           // created_at
     1;                                            // bump
 
+/// SchemaRecord account size
+pub const SCHEMA_RECORD_SIZE: usize = 8 +      // discriminator
+    32 +                                          // schema
+    4 + MAX_SCHEMA_NAME_LEN +                     // name
+    32 +                                          // layout_hash
+    1 + 8 +                                       // fee (Option<u64>)
+    1 +                                           // active
+    1 +                                           // version
+    1;                                            // bump
+
+/// AttestationRequest PDA seed for a request_id, hashed so ids longer than 32 bytes fit
+pub fn request_id_seed(request_id: &str) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hash(request_id.as_bytes()).to_bytes()
+}
+
 // ============================================
 // Accounts
 // ============================================
@@ -39,6 +120,46 @@ pub struct AttestationConfig {
     pub fee_recipient: Pubkey,
     /// PDA bump seed
     pub bump: u8,
+    /// Proposed authority, must accept before taking over
+    pub pending_authority: Option<Pubkey>,
+    /// Emergency key that can pause (but not unpause)
+    pub guardian: Option<Pubkey>,
+    /// Pause bitflags from `pause_flags`
+    pub paused: u8,
+    /// Layout version, see ATTESTATION_CONFIG_VERSION
+    pub version: u8,
+}
+
+/// Deployed AttestationConfig layout before `version` was added, ends at `bump`
+/// (read by migrate_config only)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AttestationConfigV0 {
+    pub authority: Pubkey,
+    pub operators: Vec<Pubkey>,
+    pub schema_count: u64,
+    pub attestation_fee: u64,
+    pub fee_recipient: Pubkey,
+    pub bump: u8,
+}
+
+/// AttestationConfigV0 account size
+pub const ATTESTATION_CONFIG_V0_SIZE: usize = 8 + 32 + 4 + (32 * MAX_OPERATORS) + 8 + 8 + 32 + 1;
+
+impl From<AttestationConfigV0> for AttestationConfig {
+    fn from(old: AttestationConfigV0) -> Self {
+        AttestationConfig {
+            authority: old.authority,
+            operators: old.operators,
+            schema_count: old.schema_count,
+            attestation_fee: old.attestation_fee,
+            fee_recipient: old.fee_recipient,
+            bump: old.bump,
+            pending_authority: None,
+            guardian: None,
+            paused: 0,
+            version: 0,
+        }
+    }
 }
 
 impl AttestationConfig {
@@ -51,6 +172,84 @@ impl AttestationConfig {
     pub fn is_admin(&self, key: &Pubkey) -> bool {
         self.authority == *key
     }
+
+    /// Check if any of `flags` is paused
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+
+    /// Check if the key is admin or guardian
+    pub fn is_admin_or_guardian(&self, key: &Pubkey) -> bool {
+        self.authority == *key || self.guardian == Some(*key)
+    }
+
+    /// Check if the key holds `permission`
+    /// Admin holds every permission, operators hold `permissions::OPERATOR_DEFAULT`,
+    /// anything else needs a matching role
+    pub fn has_permission(&self, key: &Pubkey, role: Option<&RoleAccount>, permission: u32) -> bool {
+        self.is_admin(key)
+            || (self.operators.contains(key) && permissions::OPERATOR_DEFAULT & permission == permission)
+            || role.is_some_and(|role| role.grants(key, permission))
+    }
+}
+
+/// Role holder account, one PDA per holder
+#[account]
+#[derive(Default)]
+pub struct RoleAccount {
+    /// Key this role belongs to
+    pub holder: Pubkey,
+    /// Permission bitflags from `permissions`
+    pub permissions: u32,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RoleAccount {
+    /// Check if this role belongs to `key` and includes `permission`
+    pub fn grants(&self, key: &Pubkey, permission: u32) -> bool {
+        self.holder == *key && self.permissions & permission == permission
+    }
+}
+
+/// Registry entry for a SAS schema created or registered by this program
+/// request_attestation / create_attestation only accept schemas with an active record
+#[account]
+#[derive(Default)]
+pub struct SchemaRecord {
+    /// SAS schema account
+    pub schema: Pubkey,
+    /// Schema name, as stored in SAS
+    pub name: String,
+    /// sha256 of the SAS layout bytes
+    pub layout_hash: [u8; 32],
+    /// Fee for this schema (in lamports), overrides AttestationConfig.attestation_fee
+    pub fee: Option<u64>,
+    /// Inactive schemas reject new requests and attestations
+    pub active: bool,
+    /// SAS schema version
+    pub version: u8,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+/// Paid attestation request, one PDA per user and request_id
+/// Holds the fee in escrow while pending, closed once approved, rejected or refunded
+#[account]
+#[derive(Default)]
+pub struct AttestationRequest {
+    /// User who paid, also the attestation subject
+    pub user: Pubkey,
+    /// SAS schema requested
+    pub schema: Pubkey,
+    /// Backend request id
+    pub request_id: String,
+    /// Escrowed fee (in lamports), on top of the account's rent
+    pub amount: u64,
+    /// Unix timestamp of the request
+    pub created_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
 }
 
 // ============================================
@@ -62,6 +261,30 @@ pub struct ConfigInitialized {
     pub authority: Pubkey,
 }
 
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct ConfigMigrated {
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
 #[event]
 pub struct OperatorAdded {
     pub operator: Pubkey,
@@ -72,12 +295,54 @@ pub struct OperatorRemoved {
     pub operator: Pubkey,
 }
 
+#[event]
+pub struct RoleGranted {
+    pub holder: Pubkey,
+    pub permissions: u32,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct GuardianSet {
+    pub guardian: Option<Pubkey>,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub paused: u8,
+    pub changed_by: Pubkey,
+}
+
 #[event]
 pub struct SchemaCreated {
     pub schema: Pubkey,
     pub name: String,
 }
 
+#[event]
+pub struct SchemaRegistered {
+    pub schema: Pubkey,
+    pub name: String,
+    pub layout_hash: [u8; 32],
+    pub version: u8,
+}
+
+#[event]
+pub struct SchemaStatusChanged {
+    pub schema: Pubkey,
+    pub active: bool,
+}
+
+#[event]
+pub struct SchemaFeeSet {
+    pub schema: Pubkey,
+    pub fee: Option<u64>,
+}
+
 #[event]
 pub struct AttestationCreated {
     pub attestation: Pubkey,
@@ -97,6 +362,20 @@ pub struct CredentialCreated {
     pub name: String,
 }
 
+#[event]
+pub struct CredentialSignerAdded {
+    pub credential: Pubkey,
+    pub signer: Pubkey,
+    pub signers: Vec<Pubkey>, // SAS signers of the credential after the change
+}
+
+#[event]
+pub struct CredentialSignerRemoved {
+    pub credential: Pubkey,
+    pub signer: Pubkey,
+    pub signers: Vec<Pubkey>, // SAS signers of the credential after the change
+}
+
 #[event]
 pub struct AttestationRequested {
     pub user: Pubkey,
@@ -105,6 +384,29 @@ pub struct AttestationRequested {
     pub amount: u64,
 }
 
+#[event]
+pub struct AttestationRequestApproved {
+    pub request: Pubkey,
+    pub attestation: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AttestationRequestRejected {
+    pub request: Pubkey,
+    pub user: Pubkey,
+    pub request_id: String,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct AttestationRequestRefunded {
+    pub request: Pubkey,
+    pub user: Pubkey,
+    pub request_id: String,
+    pub refunded: u64,
+}
+
 // ============================================
 // Instruction Contexts for Admin Settings
 // ============================================
@@ -134,3 +436,40 @@ pub struct SetFeeRecipient<'info> {
     )]
     pub config: Account<'info, AttestationConfig>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_full_v0_config() {
+        let old = AttestationConfigV0 {
+            authority: Pubkey::new_unique(),
+            operators: (0..MAX_OPERATORS).map(|_| Pubkey::new_unique()).collect(),
+            schema_count: 3,
+            attestation_fee: 1_000,
+            fee_recipient: Pubkey::new_unique(),
+            bump: 254,
+        };
+
+        // A full operator list fills the deployed account with no padding left
+        let mut data = AttestationConfig::DISCRIMINATOR.to_vec();
+        old.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ATTESTATION_CONFIG_V0_SIZE);
+
+        let config = AttestationConfig::from(AttestationConfigV0::deserialize(&mut &data[8..]).unwrap());
+        assert_eq!(config.authority, old.authority);
+        assert_eq!(config.operators, old.operators);
+        assert_eq!(config.schema_count, 3);
+        assert_eq!(config.attestation_fee, 1_000);
+        assert_eq!(config.fee_recipient, old.fee_recipient);
+        assert_eq!(config.bump, 254);
+        assert_eq!(config.pending_authority, None);
+        assert_eq!(config.guardian, None);
+        assert_eq!(config.paused, 0);
+
+        let mut migrated = Vec::new();
+        config.try_serialize(&mut migrated).unwrap();
+        assert!(migrated.len() <= ATTESTATION_CONFIG_SIZE);
+    }
+}
@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+use solana_attestation_service_client::accounts::Schema;
+use crate::error::ErrorCode;
+
+/// Maximum byte length of a single string in attestation data
+pub const MAX_ATTESTATION_STRING_LEN: usize = 256;
+
+/// birth_year fields must fall in this range (upper bound is the current year)
+pub const MIN_BIRTH_YEAR: u64 = 1900;
+
+/// Field whose integer value is range-checked as a year of birth
+const BIRTH_YEAR_FIELD: &str = "birth_year";
+
+// SAS layout type codes, vectors (13..=25) hold the scalar `code - 13`
+const TYPE_U8: u8 = 0;
+const TYPE_U16: u8 = 1;
+const TYPE_U32: u8 = 2;
+const TYPE_U64: u8 = 3;
+const TYPE_U128: u8 = 4;
+const TYPE_I8: u8 = 5;
+const TYPE_I16: u8 = 6;
+const TYPE_I32: u8 = 7;
+const TYPE_I64: u8 = 8;
+const TYPE_I128: u8 = 9;
+const TYPE_BOOL: u8 = 10;
+const TYPE_CHAR: u8 = 11;
+const TYPE_STRING: u8 = 12;
+const TYPE_VEC_OFFSET: u8 = 13;
+const TYPE_VEC_STRING: u8 = 25;
+
+/// Decode `data` with the SAS schema's layout, so a bad payload fails with the
+/// offending field logged instead of an opaque SAS CPI error
+pub fn validate_attestation_data(schema_info: &AccountInfo, data: &[u8]) -> Result<()> {
+    let schema = {
+        let account_data = schema_info.try_borrow_data()?;
+        Schema::from_bytes(&account_data).map_err(|_| ErrorCode::InvalidSchemaAccount)?
+    };
+    let field_names = parse_field_names(&schema.field_names, schema.layout.len())?;
+
+    validate_fields(&schema.layout, &field_names, data)
+}
+
+/// Decode `data` field by field, `field_names` has one name per layout entry
+fn validate_fields(layout: &[u8], field_names: &[String], data: &[u8]) -> Result<()> {
+    let mut rest = data;
+    for (index, (&type_code, field)) in layout.iter().zip(field_names).enumerate() {
+        if let Err(error) = read_field(&mut rest, type_code, field) {
+            msg!("Invalid attestation data: field '{}' (#{}, type {})", field, index, type_code);
+            return Err(error);
+        }
+    }
+
+    if !rest.is_empty() {
+        msg!("Invalid attestation data: {} trailing bytes after the last field", rest.len());
+        return err!(ErrorCode::InvalidData);
+    }
+
+    Ok(())
+}
+
+/// SAS stores field names as a borsh Vec<String>, one name per layout entry
+fn parse_field_names(bytes: &[u8], expected: usize) -> Result<Vec<String>> {
+    let names = Vec::<String>::try_from_slice(bytes).map_err(|_| ErrorCode::InvalidSchemaAccount)?;
+    require!(names.len() == expected, ErrorCode::InvalidSchemaAccount);
+    Ok(names)
+}
+
+/// Split off the next `len` bytes
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    require!(rest.len() >= len, ErrorCode::DataTruncated);
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+fn read_len(rest: &mut &[u8]) -> Result<usize> {
+    let bytes = take(rest, 4)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+}
+
+fn read_field(rest: &mut &[u8], type_code: u8, field: &str) -> Result<()> {
+    match type_code {
+        TYPE_U8..=TYPE_CHAR => read_scalar(rest, type_code, field),
+        TYPE_STRING => read_string(rest),
+        TYPE_VEC_OFFSET..=TYPE_VEC_STRING => {
+            let count = read_len(rest)?;
+            let element = type_code - TYPE_VEC_OFFSET;
+            for _ in 0..count {
+                match element {
+                    TYPE_STRING => read_string(rest)?,
+                    _ => read_scalar(rest, element, field)?,
+                }
+            }
+            Ok(())
+        }
+        _ => err!(ErrorCode::UnsupportedFieldType),
+    }
+}
+
+fn read_scalar(rest: &mut &[u8], type_code: u8, field: &str) -> Result<()> {
+    let width = match type_code {
+        TYPE_U8 | TYPE_I8 | TYPE_BOOL => 1,
+        TYPE_U16 | TYPE_I16 => 2,
+        TYPE_U32 | TYPE_I32 | TYPE_CHAR => 4,
+        TYPE_U64 | TYPE_I64 => 8,
+        TYPE_U128 | TYPE_I128 => 16,
+        _ => return err!(ErrorCode::UnsupportedFieldType),
+    };
+    let bytes = take(rest, width)?;
+
+    match type_code {
+        TYPE_BOOL => require!(bytes[0] <= 1, ErrorCode::InvalidBool),
+        TYPE_CHAR => require!(
+            char::from_u32(u32::from_le_bytes(bytes.try_into().unwrap())).is_some(),
+            ErrorCode::InvalidString
+        ),
+        TYPE_U16 | TYPE_U32 | TYPE_U64 if field == BIRTH_YEAR_FIELD => {
+            let mut value = [0u8; 8];
+            value[..width].copy_from_slice(bytes);
+            check_birth_year(u64::from_le_bytes(value))?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn read_string(rest: &mut &[u8]) -> Result<()> {
+    let len = read_len(rest)?;
+    require!(len <= MAX_ATTESTATION_STRING_LEN, ErrorCode::StringTooLong);
+    let bytes = take(rest, len)?;
+    require!(std::str::from_utf8(bytes).is_ok(), ErrorCode::InvalidString);
+    Ok(())
+}
+
+fn check_birth_year(year: u64) -> Result<()> {
+    require!(year >= MIN_BIRTH_YEAR, ErrorCode::FieldOutOfRange);
+
+    // Unix time -> calendar year, off by at most a day around new year
+    let current_year = 1970 + Clock::get()?.unix_timestamp.max(0) as u64 / 31_556_952;
+    require!(year <= current_year, ErrorCode::FieldOutOfRange);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut bytes = (value.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn parses_borsh_field_names() {
+        let bytes = names(&["name", "verified"]).try_to_vec().unwrap();
+        assert_eq!(parse_field_names(&bytes, 2).unwrap(), names(&["name", "verified"]));
+    }
+
+    #[test]
+    fn rejects_malformed_field_names() {
+        let bytes = names(&["name", "verified"]).try_to_vec().unwrap();
+        // Count doesn't match the layout
+        assert_eq!(
+            parse_field_names(&bytes, 3).unwrap_err(),
+            ErrorCode::InvalidSchemaAccount.into()
+        );
+        // Names without the leading count
+        let mut bare = string("name");
+        bare.extend(string("verified"));
+        assert_eq!(
+            parse_field_names(&bare, 2).unwrap_err(),
+            ErrorCode::InvalidSchemaAccount.into()
+        );
+    }
+
+    #[test]
+    fn accepts_valid_payload() {
+        let layout = [TYPE_STRING, TYPE_BOOL, TYPE_U8, TYPE_VEC_OFFSET + TYPE_U16, TYPE_VEC_STRING];
+        let field_names = names(&["name", "verified", "level", "scores", "tags"]);
+
+        let mut data = string("alice");
+        data.push(1);
+        data.push(7);
+        data.extend(2u32.to_le_bytes());
+        data.extend(10u16.to_le_bytes());
+        data.extend(20u16.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(string("kyc"));
+
+        assert!(validate_fields(&layout, &field_names, &data).is_ok());
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let result = validate_fields(&[TYPE_U64], &names(&["amount"]), &[0; 4]);
+        assert_eq!(result.unwrap_err(), ErrorCode::DataTruncated.into());
+    }
+
+    #[test]
+    fn rejects_trailing_data() {
+        let result = validate_fields(&[TYPE_U8], &names(&["level"]), &[1, 2]);
+        assert_eq!(result.unwrap_err(), ErrorCode::InvalidData.into());
+    }
+
+    #[test]
+    fn rejects_unsupported_field_type() {
+        let result = validate_fields(&[TYPE_VEC_STRING + 1], &names(&["unknown"]), &[0; 4]);
+        assert_eq!(result.unwrap_err(), ErrorCode::UnsupportedFieldType.into());
+    }
+
+    #[test]
+    fn rejects_invalid_bool() {
+        let result = validate_fields(&[TYPE_BOOL], &names(&["verified"]), &[2]);
+        assert_eq!(result.unwrap_err(), ErrorCode::InvalidBool.into());
+    }
+
+    #[test]
+    fn rejects_invalid_strings() {
+        let mut invalid_utf8 = 2u32.to_le_bytes().to_vec();
+        invalid_utf8.extend([0xff, 0xfe]);
+        let result = validate_fields(&[TYPE_STRING], &names(&["name"]), &invalid_utf8);
+        assert_eq!(result.unwrap_err(), ErrorCode::InvalidString.into());
+
+        let result = validate_fields(&[TYPE_CHAR], &names(&["initial"]), &0xD800u32.to_le_bytes());
+        assert_eq!(result.unwrap_err(), ErrorCode::InvalidString.into());
+    }
+
+    #[test]
+    fn rejects_long_string() {
+        let data = string(&"a".repeat(MAX_ATTESTATION_STRING_LEN + 1));
+        let result = validate_fields(&[TYPE_STRING], &names(&["name"]), &data);
+        assert_eq!(result.unwrap_err(), ErrorCode::StringTooLong.into());
+    }
+
+    #[test]
+    fn rejects_birth_year_out_of_range() {
+        let result = validate_fields(&[TYPE_U16], &names(&[BIRTH_YEAR_FIELD]), &1800u16.to_le_bytes());
+        assert_eq!(result.unwrap_err(), ErrorCode::FieldOutOfRange.into());
+    }
+}
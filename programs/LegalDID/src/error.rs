@@ -71,4 +71,96 @@ pub enum ErrorCode {
     // 19
     #[msg("Invalid mint account - does not match expected PDA.")]
     InvalidMintAccount,
+
+    // 20
+    #[msg("Order is not pending.")]
+    OrderNotPending,
+
+    // 21
+    #[msg("Order wallet doesn't match.")]
+    OrderWalletMismatch,
+
+    // 22
+    #[msg("Order cannot be reclaimed yet.")]
+    ReclaimTooEarly,
+
+    // 23
+    #[msg("Invalid payer - does not match order payer.")]
+    InvalidOrderPayer,
+
+    // 24
+    #[msg("No pending authority transfer.")]
+    NoPendingAuthority,
+
+    // 25
+    #[msg("Signer is not the pending authority.")]
+    InvalidPendingAuthority,
+
+    // 26
+    #[msg("Invalid role permissions.")]
+    InvalidPermissions,
+
+    // 27
+    #[msg("Payment mint doesn't match the order.")]
+    PaymentMintMismatch,
+
+    // 28
+    #[msg("Mint voucher has expired.")]
+    VoucherExpired,
+
+    // 29
+    #[msg("Revenue shares must be unique, non-zero and sum to 10000 bps.")]
+    InvalidRevenueShares,
+
+    // 30
+    #[msg("Revenue recipient accounts don't match the configured shares.")]
+    RevenueRecipientMismatch,
+
+    // 31
+    #[msg("This path is paused.")]
+    ProgramPaused,
+
+    // 32
+    #[msg("Invalid pause flags - guardian can only add pause bits.")]
+    InvalidPauseFlags,
+
+    // 33
+    #[msg("Account is already on the current layout version.")]
+    AlreadyMigrated,
+
+    // 34
+    #[msg("Name exceeds the maximum length.")]
+    NameTooLong,
+
+    // 35
+    #[msg("Symbol exceeds the maximum length.")]
+    SymbolTooLong,
+
+    // 36
+    #[msg("Base URI exceeds the maximum length.")]
+    BaseUriTooLong,
+
+    // 37
+    #[msg("Instruction doesn't match the project's issuance mode.")]
+    WrongIssuanceMode,
+
+    // 38
+    #[msg("Merkle tree doesn't match the project.")]
+    InvalidMerkleTree,
+
+    // 39
+    #[msg("Metaplex metadata accounts are required by this project.")]
+    MetaplexAccountsMissing,
+
+    // 40
+    #[msg("Payment mint still holds escrow for pending orders.")]
+    EscrowPending,
+
+    // 41
+    #[msg("Arithmetic overflow.")]
+    Overflow,
+
+    // 42
+    #[msg("Project already has a merkle tree.")]
+    MerkleTreeAlreadyInitialized,
 }
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::TokenInterface;
 use spl_token_2022::extension::group_member_pointer::instruction::initialize as init_group_member_pointer;
@@ -7,8 +8,12 @@ use spl_token_2022::extension::metadata_pointer::instruction::initialize as init
 use spl_token_2022::extension::ExtensionType;
 use spl_token_2022::instruction::initialize_mint_close_authority;
 use spl_token_group_interface::instruction::initialize_member;
-use spl_token_metadata_interface::instruction::initialize as init_token_metadata;
-use spl_token_metadata_interface::state::TokenMetadata;
+use spl_token_metadata_interface::instruction::{initialize as init_token_metadata, update_field};
+use mpl_token_metadata::instructions::{
+    CreateV1CpiBuilder, MintV1CpiBuilder, VerifyCollectionV1CpiBuilder,
+};
+use mpl_token_metadata::types::{Collection, PrintSupply, TokenStandard};
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
 
 use crate::state::*;
 
@@ -28,14 +33,64 @@ pub struct MintNonTransferableNft<'info> {
 
     #[account(
         mut,
-        constraint = non_transferable_project.is_admin_or_operator(&authority.key()) @ crate::error::ErrorCode::Unauthorized,
-        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes()],
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::MINT) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        constraint = non_transferable_project.issuance_mode == IssuanceMode::Token2022 @ crate::error::ErrorCode::WrongIssuanceMode,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
         bump = non_transferable_project.bump
     )]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    /// Paid order created by authorize_mint
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &hash_seed(&order_id)],
+        bump = order.bump,
+        constraint = order.status == OrderStatus::Pending @ crate::error::ErrorCode::OrderNotPending,
+        constraint = order.wallet == wallet @ crate::error::ErrorCode::OrderWalletMismatch
+    )]
+    pub order: Box<Account<'info, OrderAccount>>,
+
+    /// SPL orders only: payment mint config the order's escrow is counted on
+    #[account(
+        mut,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Option<Box<Account<'info, PaymentMintAccount>>>,
+
+    /// Wallet -> DID mint index (one DID per wallet)
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = WALLET_INDEX_SIZE,
+        seeds = [WALLET_INDEX_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump,
+        constraint = wallet_index.mint == Pubkey::default() @ crate::error::ErrorCode::AlreadyMinted
+    )]
+    pub wallet_index: Box<Account<'info, WalletIndex>>,
+
+    /// CHECK: Blacklist entry PDA for the wallet - must not exist
+    #[account(
+        seeds = [BLOCKED_WALLET_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump,
+        constraint = wallet_blacklist.data_is_empty() @ crate::error::ErrorCode::WalletBlacklisted
+    )]
+    pub wallet_blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Blacklist entry PDA for the LDID (merkle root) - must not exist
+    #[account(
+        seeds = [BLOCKED_LDID_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &ldid_seed(&merkle_root)],
+        bump,
+        constraint = ldid_blacklist.data_is_empty() @ crate::error::ErrorCode::LdidBlacklisted
+    )]
+    pub ldid_blacklist: UncheckedAccount<'info>,
+
     /// CHECK: Token-2022 NFT Mint account, requires manual extension initialization
-    /// Uses order_id as PDA seed
+    /// Uses project + order_id as PDA seed
     #[account(mut)]
     pub non_transferable_nft_mint: UncheckedAccount<'info>,
 
@@ -50,11 +105,34 @@ pub struct MintNonTransferableNft<'info> {
     /// CHECK: Collection Mint (Group)
     #[account(
         mut,
-        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes()],
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(non_transferable_project.project_id, &non_transferable_project.key())],
         bump = non_transferable_project.mint_bump,
     )]
     pub collection_mint: UncheckedAccount<'info>,
 
+    /// CHECK: Metaplex metadata PDA of the DID mint (Metaplex projects only), created by Token Metadata
+    #[account(mut)]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition PDA of the DID mint (Metaplex projects only), created by Token Metadata
+    #[account(mut)]
+    pub nft_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex metadata PDA of the collection mint, checked by Token Metadata
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition PDA of the collection mint, checked by Token Metadata
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, required by Token Metadata
+    #[account(address = instructions_sysvar::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -67,29 +145,161 @@ pub fn handler(
     wallet: Pubkey,
     merkle_root: String,
 ) -> Result<()> {
+    let merkle_root = crate::utils::canonical_hex32(&merkle_root)?;
+
+    mint_did(
+        &DidMintAccounts {
+            payer: &ctx.accounts.authority.to_account_info(),
+            project: &ctx.accounts.non_transferable_project,
+            nft_mint: &ctx.accounts.non_transferable_nft_mint.to_account_info(),
+            user_account: &ctx.accounts.user_account.to_account_info(),
+            user_token_account: &ctx.accounts.user_token_account.to_account_info(),
+            collection_mint: &ctx.accounts.collection_mint.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            program_id: ctx.program_id,
+            metaplex: MetaplexAccounts::from_optional(
+                ctx.accounts.nft_metadata.as_deref(),
+                ctx.accounts.nft_master_edition.as_deref(),
+                ctx.accounts.collection_metadata.as_deref(),
+                ctx.accounts.collection_master_edition.as_deref(),
+                ctx.accounts.token_metadata_program.as_deref(),
+                ctx.accounts.sysvar_instructions.as_deref(),
+            ),
+        },
+        &order_id,
+        wallet,
+        &merkle_root,
+    )?;
+
+    let accounts = &mut *ctx.accounts;
+    accounts.order.release_escrow(
+        &mut accounts.non_transferable_project,
+        accounts.payment_mint_config.as_deref_mut().map(|config| &mut **config),
+    )?;
+    accounts.order.status = OrderStatus::Fulfilled;
+
     let project = &mut ctx.accounts.non_transferable_project;
+    project.total_minted = project.total_minted.checked_add(1).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    let wallet_index = &mut ctx.accounts.wallet_index;
+    wallet_index.mint = ctx.accounts.non_transferable_nft_mint.key();
+    wallet_index.bump = ctx.bumps.wallet_index;
+
+    msg!("NFT minted successfully (NonTransferable, merkle_root in metadata)");
+    msg!("Order ID: {}", order_id);
+    
+    // 输出格式化的事件日志，方便后端解析
+    msg!(
+        "AirdropV4:orderId:{};wallet:{};mint:{};merkleRoot:{};",
+        order_id,
+        wallet,
+        ctx.accounts.non_transferable_nft_mint.key(),
+        merkle_root
+    );
+
+    emit!(AirdropV4 {
+        order_id: order_id.clone(),
+        wallet,
+        mint: ctx.accounts.non_transferable_nft_mint.key(),
+        merkle_root: merkle_root.clone(),
+    });
+
+    Ok(())
+}
+
+/// Accounts needed to mint one DID, shared by airdrop, claim_did and airdrop_batch
+pub struct DidMintAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub project: &'a Account<'info, ProjectAccount>,
+    pub nft_mint: &'a AccountInfo<'info>,
+    pub user_account: &'a AccountInfo<'info>,
+    pub user_token_account: &'a AccountInfo<'info>,
+    pub collection_mint: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub program_id: &'a Pubkey,
+    pub metaplex: Option<MetaplexAccounts<'a, 'info>>, // Required when the project has metaplex_metadata set
+}
+
+/// Metaplex Token Metadata accounts for one DID and its collection
+pub struct MetaplexAccounts<'a, 'info> {
+    pub metadata: &'a AccountInfo<'info>,
+    pub master_edition: &'a AccountInfo<'info>,
+    pub collection_metadata: &'a AccountInfo<'info>,
+    pub collection_master_edition: &'a AccountInfo<'info>,
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub sysvar_instructions: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> MetaplexAccounts<'a, 'info> {
+    /// Bundle a context's optional Metaplex accounts, None unless all of them were passed
+    pub fn from_optional(
+        metadata: Option<&'a AccountInfo<'info>>,
+        master_edition: Option<&'a AccountInfo<'info>>,
+        collection_metadata: Option<&'a AccountInfo<'info>>,
+        collection_master_edition: Option<&'a AccountInfo<'info>>,
+        token_metadata_program: Option<&'a AccountInfo<'info>>,
+        sysvar_instructions: Option<&'a AccountInfo<'info>>,
+    ) -> Option<Self> {
+        Some(MetaplexAccounts {
+            metadata: metadata?,
+            master_edition: master_edition?,
+            collection_metadata: collection_metadata?,
+            collection_master_edition: collection_master_edition?,
+            token_metadata_program: token_metadata_program?,
+            sysvar_instructions: sysvar_instructions?,
+        })
+    }
+}
+
+/// Create the Token-2022 DID mint (extensions, metadata, collection membership),
+/// the holder's ATA if missing, and mint 1 token to `wallet`
+/// Metaplex projects also get Metaplex metadata + master edition, verified into the collection.
+/// The master edition takes over the mint authority, so their token is minted through Token Metadata
+pub fn mint_did(
+    accounts: &DidMintAccounts,
+    order_id: &str,
+    wallet: Pubkey,
+    merkle_root: &str,
+) -> Result<()> {
+    // merkle_root must be exactly 32 bytes of hex, stored in canonical form
+    let merkle_root = &crate::utils::canonical_hex32(merkle_root)?;
+
+    require!(
+        accounts.user_account.key() == wallet,
+        crate::error::ErrorCode::OrderWalletMismatch
+    );
+
+    let project = accounts.project;
     
     let project_bump = project.bump;
+    let project_id_bytes = project_id_seed(project.project_id);
     let project_signer_seeds: &[&[u8]] = &[
         NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
         &[project_bump],
     ];
 
-    // Use order_id hash as PDA seed (to support long order IDs like UUIDs)
-    let order_id_hash = crate::state::hash_seed(&order_id);
+    // Use project + order_id hash as PDA seed (to support long order IDs like UUIDs)
+    let project_scope = project_scope_seed(project.project_id, &accounts.project.key());
+    let order_id_hash = crate::state::hash_seed(order_id);
     let (expected_mint, mint_bump) = Pubkey::find_program_address(
-        &[NON_TRANSFERABLE_NFT_MINT_PREFIX.as_bytes(), &order_id_hash],
-        ctx.program_id,
+        &[NON_TRANSFERABLE_NFT_MINT_PREFIX.as_bytes(), &project_scope, &order_id_hash],
+        accounts.program_id,
     );
     
     // Verify the provided mint account matches expected PDA
     require!(
-        ctx.accounts.non_transferable_nft_mint.key() == expected_mint,
+        accounts.nft_mint.key() == expected_mint,
         crate::error::ErrorCode::InvalidMintAccount
     );
     
     let mint_signer_seeds: &[&[u8]] = &[
         NON_TRANSFERABLE_NFT_MINT_PREFIX.as_bytes(),
+        &project_scope,
         &order_id_hash,
         &[mint_bump],
     ];
@@ -100,24 +310,32 @@ pub fn handler(
     let metadata_uri = format!("{}{}.json", project.base_uri, merkle_root);
     let name = format!("LDID #{}", order_id);  // Use order_id in name
     let symbol = project.symbol.clone();
+    let issued_at = Clock::get()?.unix_timestamp.to_string();
+
+    // Additional metadata readable on-chain without fetching the off-chain JSON
+    let additional_metadata = vec![
+        (MERKLE_ROOT_METADATA_KEY.to_string(), merkle_root.to_string()),
+        (ORDER_ID_METADATA_KEY.to_string(), order_id.to_string()),
+        (ISSUED_AT_METADATA_KEY.to_string(), issued_at),
+    ];
 
     // 1. Create Token-2022 NFT Mint (if not exists)
     require!(
-        ctx.accounts.non_transferable_nft_mint.data_is_empty(),
+        accounts.nft_mint.data_is_empty(),
         crate::error::ErrorCode::LDIDHasMinted
     );
 
     msg!("Creating Token-2022 NFT Mint with NonTransferable + PermanentDelegate + MetadataPointer + MintCloseAuthority");
 
     let metadata = TokenMetadata {
-        update_authority: Some(ctx.accounts.non_transferable_project.key())
+        update_authority: Some(accounts.project.key())
             .try_into()
             .unwrap(),
-        mint: ctx.accounts.non_transferable_nft_mint.key(),
+        mint: accounts.nft_mint.key(),
         name: name.clone(),
         symbol: symbol.clone(),
         uri: metadata_uri.clone(),
-        additional_metadata: vec![],
+        additional_metadata: additional_metadata.clone(),
     };
     let metadata_space = metadata
         .tlv_size_of()
@@ -151,16 +369,16 @@ pub fn handler(
     // Step 1: Create account
     invoke_signed(
         &anchor_lang::solana_program::system_instruction::create_account(
-            &ctx.accounts.authority.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
+            &accounts.payer.key(),
+            &accounts.nft_mint.key(),
             total_rent,
             base_space as u64,
-            &ctx.accounts.token_program.key(),
+            &accounts.token_program.key(),
         ),
         &[
-            ctx.accounts.authority.to_account_info(),
-            ctx.accounts.non_transferable_nft_mint.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
+            accounts.payer.to_account_info(),
+            accounts.nft_mint.to_account_info(),
+            accounts.system_program.to_account_info(),
         ],
         &[mint_signer_seeds],
     )?;
@@ -168,102 +386,120 @@ pub fn handler(
     // Step 2: Initialize extensions in discriminant order
     invoke_signed(
         &spl_token_2022::instruction::initialize_non_transferable_mint(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
         )?,
-        &[ctx.accounts.non_transferable_nft_mint.to_account_info()],
+        &[accounts.nft_mint.to_account_info()],
         &[mint_signer_seeds],
     )?;
 
     invoke_signed(
         &spl_token_2022::instruction::initialize_permanent_delegate(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
-            &ctx.accounts.non_transferable_project.key(),
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
+            &accounts.project.key(),
         )?,
-        &[ctx.accounts.non_transferable_nft_mint.to_account_info()],
+        &[accounts.nft_mint.to_account_info()],
         &[mint_signer_seeds],
     )?;
 
     invoke_signed(
         &init_metadata_pointer(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
-            Some(ctx.accounts.non_transferable_project.key()),
-            Some(ctx.accounts.non_transferable_nft_mint.key()),
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
+            Some(accounts.project.key()),
+            Some(accounts.nft_mint.key()),
         )?,
-        &[ctx.accounts.non_transferable_nft_mint.to_account_info()],
+        &[accounts.nft_mint.to_account_info()],
         &[mint_signer_seeds],
     )?;
 
     // MintCloseAuthority - allows closing Mint account to recover rent
     invoke_signed(
         &initialize_mint_close_authority(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
-            Some(&ctx.accounts.non_transferable_project.key()),
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
+            Some(&accounts.project.key()),
         )?,
-        &[ctx.accounts.non_transferable_nft_mint.to_account_info()],
+        &[accounts.nft_mint.to_account_info()],
         &[mint_signer_seeds],
     )?;
 
     // GroupMemberPointer - points to self as Member
     invoke_signed(
         &init_group_member_pointer(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
-            Some(ctx.accounts.non_transferable_project.key()),
-            Some(ctx.accounts.non_transferable_nft_mint.key()),
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
+            Some(accounts.project.key()),
+            Some(accounts.nft_mint.key()),
         )?,
-        &[ctx.accounts.non_transferable_nft_mint.to_account_info()],
+        &[accounts.nft_mint.to_account_info()],
         &[mint_signer_seeds],
     )?;
 
     // Step 3: InitializeMint2
     invoke_signed(
         &spl_token_2022::instruction::initialize_mint2(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
-            &ctx.accounts.non_transferable_project.key(),
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
+            &accounts.project.key(),
             None,
             0,
         )?,
-        &[ctx.accounts.non_transferable_nft_mint.to_account_info()],
+        &[accounts.nft_mint.to_account_info()],
         &[mint_signer_seeds],
     )?;
 
     invoke_signed(
         &init_token_metadata(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
-            &ctx.accounts.non_transferable_project.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),
-            &ctx.accounts.non_transferable_project.key(),
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
+            &accounts.project.key(),
+            &accounts.nft_mint.key(),
+            &accounts.project.key(),
             name.clone(),
             symbol.clone(),
             metadata_uri.clone(),
         ),
         &[
-            ctx.accounts.non_transferable_nft_mint.to_account_info(),
-            ctx.accounts.non_transferable_project.to_account_info(),
+            accounts.nft_mint.to_account_info(),
+            accounts.project.to_account_info(),
         ],
         &[project_signer_seeds],
     )?;
 
+    // Write additional metadata fields (space already prepaid above)
+    for (key, value) in additional_metadata {
+        invoke_signed(
+            &update_field(
+                &accounts.token_program.key(),
+                &accounts.nft_mint.key(),
+                &accounts.project.key(),
+                Field::Key(key),
+                value,
+            ),
+            &[
+                accounts.nft_mint.to_account_info(),
+                accounts.project.to_account_info(),
+            ],
+            &[project_signer_seeds],
+        )?;
+    }
+
     // Step 6: Initialize GroupMember (join Collection)
     invoke_signed(
         &initialize_member(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.non_transferable_nft_mint.key(),  // member mint
-            &ctx.accounts.non_transferable_nft_mint.key(),  // member mint (points to self)
-            &ctx.accounts.non_transferable_project.key(),   // member mint authority
-            &ctx.accounts.collection_mint.key(),            // group mint
-            &ctx.accounts.non_transferable_project.key(),   // group update authority
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),  // member mint
+            &accounts.nft_mint.key(),  // member mint (points to self)
+            &accounts.project.key(),   // member mint authority
+            &accounts.collection_mint.key(),            // group mint
+            &accounts.project.key(),   // group update authority
         ),
         &[
-            ctx.accounts.non_transferable_nft_mint.to_account_info(),
-            ctx.accounts.collection_mint.to_account_info(),
-            ctx.accounts.non_transferable_project.to_account_info(),
+            accounts.nft_mint.to_account_info(),
+            accounts.collection_mint.to_account_info(),
+            accounts.project.to_account_info(),
         ],
         &[project_signer_seeds],
     )?;
@@ -271,69 +507,113 @@ pub fn handler(
     msg!("NFT Mint created with merkle_root in metadata, joined Collection");
 
     // 2. Create user's Token-2022 ATA (if not exists)
-    if ctx.accounts.user_token_account.data_is_empty() {
+    if accounts.user_token_account.data_is_empty() {
         msg!("Creating Token-2022 ATA for user");
 
         let create_ata_ix =
             spl_associated_token_account::instruction::create_associated_token_account(
-                &ctx.accounts.authority.key(),
-                &ctx.accounts.user_account.key(),
-                &ctx.accounts.non_transferable_nft_mint.key(),
-                &ctx.accounts.token_program.key(),
+                &accounts.payer.key(),
+                &accounts.user_account.key(),
+                &accounts.nft_mint.key(),
+                &accounts.token_program.key(),
             );
 
         anchor_lang::solana_program::program::invoke(
             &create_ata_ix,
             &[
-                ctx.accounts.authority.to_account_info(),
-                ctx.accounts.user_token_account.to_account_info(),
-                ctx.accounts.user_account.to_account_info(),
-                ctx.accounts.non_transferable_nft_mint.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
+                accounts.payer.to_account_info(),
+                accounts.user_token_account.to_account_info(),
+                accounts.user_account.to_account_info(),
+                accounts.nft_mint.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.token_program.to_account_info(),
             ],
         )?;
     }
 
     // 3. Mint NFT to user account
-    msg!("Minting Token-2022 NFT to user");
-    let mint_to_ix = spl_token_2022::instruction::mint_to(
-        &ctx.accounts.token_program.key(),
-        &ctx.accounts.non_transferable_nft_mint.key(),
-        &ctx.accounts.user_token_account.key(),
-        &ctx.accounts.non_transferable_project.key(),
-        &[],
-        1,
-    )?;
-
-    invoke_signed(
-        &mint_to_ix,
-        &[
-            ctx.accounts.non_transferable_nft_mint.to_account_info(),
-            ctx.accounts.user_token_account.to_account_info(),
-            ctx.accounts.non_transferable_project.to_account_info(),
-        ],
-        &[project_signer_seeds],
-    )?;
-
-    msg!("NFT minted successfully (NonTransferable, merkle_root in metadata)");
-    msg!("Order ID: {}", order_id);
-    
-    // 输出格式化的事件日志，方便后端解析
-    msg!(
-        "AirdropV4:orderId:{};wallet:{};mint:{};merkleRoot:{};",
-        order_id,
-        wallet,
-        ctx.accounts.non_transferable_nft_mint.key(),
-        merkle_root
-    );
+    if project.metaplex_metadata {
+        let metaplex = accounts
+            .metaplex
+            .as_ref()
+            .ok_or(crate::error::ErrorCode::MetaplexAccountsMissing)?;
+
+        // Metaplex metadata for wallets / marketplaces that don't read Token-2022 metadata.
+        // Created on the empty mint, the master edition becomes mint authority from here on
+        msg!("Creating Metaplex metadata and master edition");
+
+        let project_info = accounts.project.to_account_info();
+        let metaplex_name: String = name.chars().take(METAPLEX_MAX_NAME_LEN).collect();
+
+        CreateV1CpiBuilder::new(metaplex.token_metadata_program)
+            .metadata(metaplex.metadata)
+            .master_edition(Some(metaplex.master_edition))
+            .mint(accounts.nft_mint, false)
+            .authority(&project_info)
+            .payer(accounts.payer)
+            .update_authority(&project_info, true)
+            .system_program(accounts.system_program)
+            .sysvar_instructions(metaplex.sysvar_instructions)
+            .spl_token_program(Some(accounts.token_program))
+            .name(metaplex_name)
+            .symbol(symbol.clone())
+            .uri(metadata_uri.clone())
+            .seller_fee_basis_points(0)
+            .is_mutable(true)
+            .token_standard(TokenStandard::NonFungible)
+            .collection(Collection {
+                verified: false,
+                key: accounts.collection_mint.key(),
+            })
+            .print_supply(PrintSupply::Zero)
+            .invoke_signed(&[project_signer_seeds])?;
+
+        msg!("Minting Token-2022 NFT to user through Token Metadata");
+        MintV1CpiBuilder::new(metaplex.token_metadata_program)
+            .token(accounts.user_token_account)
+            .token_owner(Some(accounts.user_account))
+            .metadata(metaplex.metadata)
+            .master_edition(Some(metaplex.master_edition))
+            .mint(accounts.nft_mint)
+            .authority(&project_info)
+            .payer(accounts.payer)
+            .system_program(accounts.system_program)
+            .sysvar_instructions(metaplex.sysvar_instructions)
+            .spl_token_program(accounts.token_program)
+            .spl_ata_program(accounts.associated_token_program)
+            .amount(1)
+            .invoke_signed(&[project_signer_seeds])?;
+
+        VerifyCollectionV1CpiBuilder::new(metaplex.token_metadata_program)
+            .authority(&project_info)
+            .metadata(metaplex.metadata)
+            .collection_mint(accounts.collection_mint)
+            .collection_metadata(Some(metaplex.collection_metadata))
+            .collection_master_edition(Some(metaplex.collection_master_edition))
+            .system_program(accounts.system_program)
+            .sysvar_instructions(metaplex.sysvar_instructions)
+            .invoke_signed(&[project_signer_seeds])?;
+    } else {
+        msg!("Minting Token-2022 NFT to user");
+        let mint_to_ix = spl_token_2022::instruction::mint_to(
+            &accounts.token_program.key(),
+            &accounts.nft_mint.key(),
+            &accounts.user_token_account.key(),
+            &accounts.project.key(),
+            &[],
+            1,
+        )?;
 
-    emit!(AirdropV4 {
-        order_id: order_id.clone(),
-        wallet,
-        mint: ctx.accounts.non_transferable_nft_mint.key(),
-        merkle_root: merkle_root.clone(),
-    });
+        invoke_signed(
+            &mint_to_ix,
+            &[
+                accounts.nft_mint.to_account_info(),
+                accounts.user_token_account.to_account_info(),
+                accounts.project.to_account_info(),
+            ],
+            &[project_signer_seeds],
+        )?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,272 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+use anchor_lang::system_program::{allocate, assign, transfer, Allocate, Assign, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenInterface;
+
+use crate::instructions::airdrop::{mint_did, AirdropV4, DidMintAccounts};
+use crate::state::*;
+
+/// One DID to mint in airdrop_batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AirdropEntry {
+    pub order_id: String,
+    pub wallet: Pubkey,
+    pub merkle_root: String,
+}
+
+#[event]
+pub struct AirdropBatchV4 {
+    pub requested: u32,
+    pub minted: u32,
+}
+
+/// Mint several DIDs in one instruction
+/// Per-entry accounts come through remaining_accounts, AIRDROP_BATCH_ACCOUNTS_PER_ENTRY each, in order:
+/// order, wallet_index, wallet_blacklist, ldid_blacklist, nft_mint, user_account, user_token_account
+/// followed by the payment mint config of every SPL payment mint used by the orders
+/// Not available to Metaplex projects, their DIDs go through airdrop / claim_did
+#[derive(Accounts)]
+pub struct AirdropBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::MINT) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        constraint = non_transferable_project.issuance_mode == IssuanceMode::Token2022 @ crate::error::ErrorCode::WrongIssuanceMode,
+        constraint = !non_transferable_project.metaplex_metadata @ crate::error::ErrorCode::MetaplexAccountsMissing,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    /// CHECK: Collection Mint (Group)
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(non_transferable_project.project_id, &non_transferable_project.key())],
+        bump = non_transferable_project.mint_bump,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mints entries in order until the compute budget runs low, the rest stay Pending
+/// for the next batch
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AirdropBatch<'info>>,
+    entries: Vec<AirdropEntry>,
+) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() >= entries.len() * AIRDROP_BATCH_ACCOUNTS_PER_ENTRY,
+        crate::error::ErrorCode::InvalidDataProvided
+    );
+    let (entry_accounts, payment_mint_configs) = ctx
+        .remaining_accounts
+        .split_at(entries.len() * AIRDROP_BATCH_ACCOUNTS_PER_ENTRY);
+
+    let project_key = ctx.accounts.non_transferable_project.key();
+    let mut minted: u32 = 0;
+
+    for (entry, accounts) in entries
+        .iter()
+        .zip(entry_accounts.chunks(AIRDROP_BATCH_ACCOUNTS_PER_ENTRY))
+    {
+        if sol_remaining_compute_units() < AIRDROP_BATCH_MIN_COMPUTE_UNITS {
+            msg!("Compute budget low, stopping after {} DIDs", minted);
+            break;
+        }
+
+        let [order_info, wallet_index_info, wallet_blacklist, ldid_blacklist, nft_mint, user_account, user_token_account] =
+            accounts
+        else {
+            return err!(crate::error::ErrorCode::InvalidDataProvided);
+        };
+
+        // Same checks as the MintNonTransferableNft context
+        let mut order = Account::<OrderAccount>::try_from(order_info)?;
+        let order_id_hash = hash_seed(&entry.order_id);
+        let expected_order = Pubkey::create_program_address(
+            &[NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), project_key.as_ref(), &order_id_hash, &[order.bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| crate::error::ErrorCode::InvalidDataProvided)?;
+        require_keys_eq!(order_info.key(), expected_order, crate::error::ErrorCode::InvalidDataProvided);
+        require!(order.status == OrderStatus::Pending, crate::error::ErrorCode::OrderNotPending);
+        require!(order.wallet == entry.wallet, crate::error::ErrorCode::OrderWalletMismatch);
+
+        let (expected_wallet_blacklist, _) = Pubkey::find_program_address(
+            &[BLOCKED_WALLET_PREFIX.as_bytes(), project_key.as_ref(), entry.wallet.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(wallet_blacklist.key(), expected_wallet_blacklist, crate::error::ErrorCode::InvalidDataProvided);
+        require!(wallet_blacklist.data_is_empty(), crate::error::ErrorCode::WalletBlacklisted);
+
+        let merkle_root = crate::utils::canonical_hex32(&entry.merkle_root)?;
+        let (expected_ldid_blacklist, _) = Pubkey::find_program_address(
+            &[BLOCKED_LDID_PREFIX.as_bytes(), project_key.as_ref(), &ldid_seed(&merkle_root)],
+            ctx.program_id,
+        );
+        require_keys_eq!(ldid_blacklist.key(), expected_ldid_blacklist, crate::error::ErrorCode::InvalidDataProvided);
+        require!(ldid_blacklist.data_is_empty(), crate::error::ErrorCode::LdidBlacklisted);
+
+        let wallet_index_bump = init_wallet_index(&ctx, wallet_index_info, &project_key, &entry.wallet)?;
+
+        mint_did(
+            &DidMintAccounts {
+                payer: &ctx.accounts.authority.to_account_info(),
+                project: &ctx.accounts.non_transferable_project,
+                nft_mint,
+                user_account,
+                user_token_account,
+                collection_mint: &ctx.accounts.collection_mint.to_account_info(),
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+                program_id: ctx.program_id,
+                metaplex: None,
+            },
+            &entry.order_id,
+            entry.wallet,
+            &merkle_root,
+        )?;
+
+        let mut payment_mint_config = order
+            .payment_mint
+            .map(|mint| load_payment_mint_config(payment_mint_configs, &project_key, &mint, ctx.program_id))
+            .transpose()?;
+        order.release_escrow(&mut ctx.accounts.non_transferable_project, payment_mint_config.as_deref_mut())?;
+        if let Some(config) = &payment_mint_config {
+            config.exit(ctx.program_id)?;
+        }
+
+        order.status = OrderStatus::Fulfilled;
+        order.exit(ctx.program_id)?;
+
+        let mut wallet_index = Account::<WalletIndex>::try_from(wallet_index_info)?;
+        wallet_index.mint = nft_mint.key();
+        wallet_index.bump = wallet_index_bump;
+        wallet_index.exit(ctx.program_id)?;
+
+        msg!(
+            "AirdropV4:orderId:{};wallet:{};mint:{};merkleRoot:{};",
+            entry.order_id,
+            entry.wallet,
+            nft_mint.key(),
+            merkle_root
+        );
+
+        emit!(AirdropV4 {
+            order_id: entry.order_id.clone(),
+            wallet: entry.wallet,
+            mint: nft_mint.key(),
+            merkle_root,
+        });
+
+        minted += 1;
+    }
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.total_minted = project.total_minted.checked_add(minted as u64).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    emit!(AirdropBatchV4 {
+        requested: entries.len() as u32,
+        minted,
+    });
+
+    Ok(())
+}
+
+/// Find the payment mint config PDA for `mint` among the trailing remaining accounts
+fn load_payment_mint_config<'info>(
+    payment_mint_configs: &'info [AccountInfo<'info>],
+    project_key: &Pubkey,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Account<'info, PaymentMintAccount>> {
+    let (expected, _) = Pubkey::find_program_address(
+        &[PAYMENT_MINT_PREFIX.as_bytes(), project_key.as_ref(), mint.as_ref()],
+        program_id,
+    );
+    let config_info = payment_mint_configs
+        .iter()
+        .find(|info| info.key() == expected)
+        .ok_or(crate::error::ErrorCode::InvalidDataProvided)?;
+
+    Account::<PaymentMintAccount>::try_from(config_info)
+}
+
+/// Create the wallet index PDA if missing (init_if_needed by hand), reject wallets that already hold a DID
+fn init_wallet_index<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, AirdropBatch<'info>>,
+    wallet_index_info: &'info AccountInfo<'info>,
+    project_key: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<u8> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[WALLET_INDEX_PREFIX.as_bytes(), project_key.as_ref(), wallet.as_ref()],
+        ctx.program_id,
+    );
+    require_keys_eq!(wallet_index_info.key(), expected, crate::error::ErrorCode::InvalidDataProvided);
+
+    if wallet_index_info.data_is_empty() {
+        // Top up, allocate and assign instead of create_account, which fails if someone
+        // already sent lamports to the PDA
+        let wallet_index_seeds: &[&[u8]] =
+            &[WALLET_INDEX_PREFIX.as_bytes(), project_key.as_ref(), wallet.as_ref(), &[bump]];
+        let top_up = Rent::get()?
+            .minimum_balance(WALLET_INDEX_SIZE)
+            .saturating_sub(wallet_index_info.lamports());
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: wallet_index_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Allocate {
+                    account_to_allocate: wallet_index_info.clone(),
+                },
+                &[wallet_index_seeds],
+            ),
+            WALLET_INDEX_SIZE as u64,
+        )?;
+        assign(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Assign {
+                    account_to_assign: wallet_index_info.clone(),
+                },
+                &[wallet_index_seeds],
+            ),
+            ctx.program_id,
+        )?;
+
+        let mut data = wallet_index_info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        WalletIndex::default().try_serialize(&mut writer)?;
+    } else {
+        let wallet_index = Account::<WalletIndex>::try_from(wallet_index_info)?;
+        require!(
+            wallet_index.mint == Pubkey::default(),
+            crate::error::ErrorCode::AlreadyMinted
+        );
+    }
+
+    Ok(bump)
+}
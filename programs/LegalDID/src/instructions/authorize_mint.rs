@@ -11,7 +11,7 @@ pub struct AuthorizeMintV4 {
 }
 
 #[derive(Accounts)]
-#[instruction(order_id: String)]
+#[instruction(order_id: String, wallet: Pubkey)]
 pub struct AuthorizeMint<'info> {
     /// Paying user (anyone can pay)
     #[account(mut)]
@@ -20,17 +20,37 @@ pub struct AuthorizeMint<'info> {
     /// Project account (holds funds until withdraw)
     #[account(
         mut,
-        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes()],
-        bump = non_transferable_project.bump
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = !non_transferable_project.is_paused(pause_flags::USER) @ crate::error::ErrorCode::ProgramPaused
     )]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 
+    /// Order record (same order_id can only be paid once)
+    #[account(
+        init,
+        payer = payer,
+        space = ORDER_ACCOUNT_SIZE,
+        seeds = [NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &hash_seed(&order_id)],
+        bump
+    )]
+    pub order: Box<Account<'info, OrderAccount>>,
+
+    /// CHECK: Blacklist entry PDA for the wallet - must not exist
+    #[account(
+        seeds = [BLOCKED_WALLET_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump,
+        constraint = wallet_blacklist.data_is_empty() @ crate::error::ErrorCode::WalletBlacklisted
+    )]
+    pub wallet_blacklist: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<AuthorizeMint>,
     order_id: String,
+    wallet: Pubkey,
 ) -> Result<()> {
     let project = &ctx.accounts.non_transferable_project;
     let mint_price = project.mint_price;
@@ -52,23 +72,37 @@ pub fn handler(
         )?;
     }
 
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.total_fees_collected = project.total_fees_collected.checked_add(mint_price).ok_or(crate::error::ErrorCode::Overflow)?;
+    project.pending_escrow = project.pending_escrow.checked_add(mint_price).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    let order = &mut ctx.accounts.order;
+    order.payer = ctx.accounts.payer.key();
+    order.wallet = wallet;
+    order.amount = mint_price;
+    order.slot = Clock::get()?.slot;
+    order.status = OrderStatus::Pending;
+    order.bump = ctx.bumps.order;
+    order.payment_mint = None;
+
     msg!("DID mint requested (payment received)");
     msg!("Order ID: {}", order_id);
     msg!("Payer: {}", ctx.accounts.payer.key());
+    msg!("Wallet: {}", wallet);
     msg!("Amount: {} lamports", mint_price);
     
     // 输出格式化的事件日志，方便后端解析
     msg!(
         "AuthorizeMintV4:orderId:{};wallet:{};payer:{};amount:{};",
         order_id,
-        ctx.accounts.payer.key(),
+        wallet,
         ctx.accounts.payer.key(),
         mint_price
     );
 
     emit!(AuthorizeMintV4 {
         order_id: order_id.clone(),
-        wallet: ctx.accounts.payer.key(),
+        wallet,
         payer: ctx.accounts.payer.key(),
         amount: mint_price,
     });
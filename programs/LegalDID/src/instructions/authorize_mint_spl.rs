@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::state::*;
+
+#[event]
+pub struct AuthorizeMintSplV4 {
+    pub order_id: String,
+    pub wallet: Pubkey,
+    pub payer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: String, wallet: Pubkey)]
+pub struct AuthorizeMintSpl<'info> {
+    /// Paying user (anyone can pay)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = !non_transferable_project.is_paused(pause_flags::USER) @ crate::error::ErrorCode::ProgramPaused
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Order record (same order_id can only be paid once)
+    #[account(
+        init,
+        payer = payer,
+        space = ORDER_ACCOUNT_SIZE,
+        seeds = [NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &hash_seed(&order_id)],
+        bump
+    )]
+    pub order: Box<Account<'info, OrderAccount>>,
+
+    /// CHECK: Blacklist entry PDA for the wallet - must not exist
+    #[account(
+        seeds = [BLOCKED_WALLET_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump,
+        constraint = wallet_blacklist.data_is_empty() @ crate::error::ErrorCode::WalletBlacklisted
+    )]
+    pub wallet_blacklist: UncheckedAccount<'info>,
+
+    /// Accepted payment mint and price, counts the vault's pending escrow
+    #[account(
+        mut,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint.key().as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Box<Account<'info, PaymentMintAccount>>,
+
+    #[account(mint::token_program = token_program)]
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = payer,
+        token::token_program = token_program,
+    )]
+    pub payer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Project vault (ATA owned by the project PDA, holds funds until withdraw_spl)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = payment_mint,
+        associated_token::authority = non_transferable_project,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<AuthorizeMintSpl>,
+    order_id: String,
+    wallet: Pubkey,
+) -> Result<()> {
+    let price = ctx.accounts.payment_mint_config.price;
+    let payment_mint = ctx.accounts.payment_mint.key();
+
+    // Transfer tokens to the project vault
+    if price > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.payer_token_account.to_account_info(),
+                    mint: ctx.accounts.payment_mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.payer.to_account_info(),
+                },
+            ),
+            price,
+            ctx.accounts.payment_mint.decimals,
+        )?;
+    }
+
+    let config = &mut ctx.accounts.payment_mint_config;
+    config.pending_escrow = config.pending_escrow.checked_add(price).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    let order = &mut ctx.accounts.order;
+    order.payer = ctx.accounts.payer.key();
+    order.wallet = wallet;
+    order.amount = price;
+    order.slot = Clock::get()?.slot;
+    order.status = OrderStatus::Pending;
+    order.bump = ctx.bumps.order;
+    order.payment_mint = Some(payment_mint);
+
+    msg!("DID mint requested (SPL payment received)");
+    msg!("Order ID: {}", order_id);
+    msg!("Payer: {}", ctx.accounts.payer.key());
+    msg!("Wallet: {}", wallet);
+    msg!("Amount: {} (mint {})", price, payment_mint);
+
+    // 输出格式化的事件日志，方便后端解析
+    msg!(
+        "AuthorizeMintSplV4:orderId:{};wallet:{};payer:{};mint:{};amount:{};",
+        order_id,
+        wallet,
+        ctx.accounts.payer.key(),
+        payment_mint,
+        price
+    );
+
+    emit!(AuthorizeMintSplV4 {
+        order_id: order_id.clone(),
+        wallet,
+        payer: ctx.accounts.payer.key(),
+        mint: payment_mint,
+        amount: price,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct BlacklistWalletV4 {
+    pub wallet: Pubkey,
+    pub blocked: bool,
+}
+
+#[event]
+pub struct BlacklistLdidV4 {
+    pub ldid: String,
+    pub blocked: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct BlockWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::MANAGE_BLACKLIST) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BLACKLIST_ENTRY_SIZE,
+        seeds = [BLOCKED_WALLET_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct UnblockWallet<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::MANAGE_BLACKLIST) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [BLOCKED_WALLET_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump = blacklist_entry.bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(ldid: String)]
+pub struct BlockLdid<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::MANAGE_BLACKLIST) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BLACKLIST_ENTRY_SIZE,
+        seeds = [BLOCKED_LDID_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &ldid_seed(&ldid)],
+        bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(ldid: String)]
+pub struct UnblockLdid<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::MANAGE_BLACKLIST) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [BLOCKED_LDID_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &ldid_seed(&ldid)],
+        bump = blacklist_entry.bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+}
+
+pub fn block_wallet_handler(ctx: Context<BlockWallet>, wallet: Pubkey) -> Result<()> {
+    let entry = &mut ctx.accounts.blacklist_entry;
+    entry.added_by = ctx.accounts.authority.key();
+    entry.added_at = Clock::get()?.unix_timestamp;
+    entry.bump = ctx.bumps.blacklist_entry;
+
+    msg!("BlacklistWalletV4:wallet:{};blocked:true;", wallet);
+
+    emit!(BlacklistWalletV4 {
+        wallet,
+        blocked: true,
+    });
+
+    Ok(())
+}
+
+pub fn unblock_wallet_handler(_ctx: Context<UnblockWallet>, wallet: Pubkey) -> Result<()> {
+    msg!("BlacklistWalletV4:wallet:{};blocked:false;", wallet);
+
+    emit!(BlacklistWalletV4 {
+        wallet,
+        blocked: false,
+    });
+
+    Ok(())
+}
+
+pub fn block_ldid_handler(ctx: Context<BlockLdid>, ldid: String) -> Result<()> {
+    let ldid = crate::utils::canonical_hex32(&ldid)?;
+
+    let entry = &mut ctx.accounts.blacklist_entry;
+    entry.added_by = ctx.accounts.authority.key();
+    entry.added_at = Clock::get()?.unix_timestamp;
+    entry.bump = ctx.bumps.blacklist_entry;
+
+    msg!("BlacklistLdidV4:ldid:{};blocked:true;", ldid);
+
+    emit!(BlacklistLdidV4 {
+        ldid,
+        blocked: true,
+    });
+
+    Ok(())
+}
+
+pub fn unblock_ldid_handler(_ctx: Context<UnblockLdid>, ldid: String) -> Result<()> {
+    let ldid = crate::utils::canonical_hex32(&ldid)?;
+
+    msg!("BlacklistLdidV4:ldid:{};blocked:false;", ldid);
+
+    emit!(BlacklistLdidV4 {
+        ldid,
+        blocked: false,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::TokenInterface;
+use mpl_token_metadata::instructions::BurnV1CpiBuilder;
 
 use crate::state::*;
 
@@ -25,11 +27,20 @@ pub struct BurnNonTransferableNft<'info> {
 
     #[account(
         mut,
-        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes()],
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
         bump = non_transferable_project.bump
     )]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 
+    /// CHECK: Holder's wallet index PDA, closed in the handler so the wallet can be re-issued
+    /// Empty for DIDs minted before wallet indexes existed
+    #[account(
+        mut,
+        seeds = [WALLET_INDEX_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), nft_owner.key().as_ref()],
+        bump
+    )]
+    pub wallet_index: UncheckedAccount<'info>,
+
     /// CHECK: User's Token Account - validated by token program
     #[account(mut)]
     pub user_token_account: UncheckedAccount<'info>,
@@ -38,6 +49,26 @@ pub struct BurnNonTransferableNft<'info> {
     #[account(mut)]
     pub non_transferable_nft_mint: UncheckedAccount<'info>,
 
+    /// CHECK: Metaplex metadata PDA of the DID mint (Metaplex projects only), closed by Token Metadata
+    #[account(mut)]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition PDA of the DID mint (Metaplex projects only), closed by Token Metadata
+    #[account(mut)]
+    pub nft_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex metadata PDA of the collection mint, its size is decremented by Token Metadata
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, required by Token Metadata
+    #[account(address = instructions_sysvar::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -49,7 +80,40 @@ pub fn handler(ctx: Context<BurnNonTransferableNft>) -> Result<()> {
 
     let token_account_info = ctx.accounts.user_token_account.to_account_info();
 
-    if !token_account_info.data_is_empty() {
+    if ctx.accounts.non_transferable_project.metaplex_metadata && !token_account_info.data_is_empty() {
+        let (
+            Some(nft_metadata),
+            Some(nft_master_edition),
+            Some(collection_metadata),
+            Some(token_metadata_program),
+            Some(sysvar_instructions),
+        ) = (
+            ctx.accounts.nft_metadata.as_deref(),
+            ctx.accounts.nft_master_edition.as_deref(),
+            ctx.accounts.collection_metadata.as_deref(),
+            ctx.accounts.token_metadata_program.as_deref(),
+            ctx.accounts.sysvar_instructions.as_deref(),
+        )
+        else {
+            return err!(crate::error::ErrorCode::MetaplexAccountsMissing);
+        };
+
+        // Token Metadata burns the token and closes the token account, metadata and
+        // master edition, rent goes back to the holder
+        msg!("Burning through Token Metadata, closing Metaplex accounts...");
+        BurnV1CpiBuilder::new(token_metadata_program)
+            .authority(&ctx.accounts.nft_owner.to_account_info())
+            .collection_metadata(Some(collection_metadata))
+            .metadata(nft_metadata)
+            .edition(Some(nft_master_edition))
+            .mint(&ctx.accounts.non_transferable_nft_mint.to_account_info())
+            .token(&token_account_info)
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .sysvar_instructions(sysvar_instructions)
+            .spl_token_program(&ctx.accounts.token_program.to_account_info())
+            .amount(1)
+            .invoke()?;
+    } else if !token_account_info.data_is_empty() {
         // Read balance
         let amount = {
             let token_account_data = token_account_info.try_borrow_data()?;
@@ -107,8 +171,10 @@ pub fn handler(ctx: Context<BurnNonTransferableNft>) -> Result<()> {
     }
 
     // Close Mint account, recover rent to admin (requires MintCloseAuthority extension)
+    let project_id_bytes = project_id_seed(ctx.accounts.non_transferable_project.project_id);
     let project_signer_seeds: &[&[u8]] = &[
         NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
         &[ctx.accounts.non_transferable_project.bump],
     ];
 
@@ -132,6 +198,15 @@ pub fn handler(ctx: Context<BurnNonTransferableNft>) -> Result<()> {
     )?;
 
     msg!("Mint account closed, rent recovered");
+
+    crate::utils::close_wallet_index(
+        &ctx.accounts.wallet_index,
+        &ctx.accounts.non_transferable_nft_mint.key(),
+        &ctx.accounts.authority,
+    )?;
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.total_burned = project.total_burned.checked_add(1).ok_or(crate::error::ErrorCode::Overflow)?;
     
     // 输出格式化的事件日志，方便后端解析
     msg!(
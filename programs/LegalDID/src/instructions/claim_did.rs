@@ -0,0 +1,241 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::TokenInterface;
+
+use crate::instructions::airdrop::{mint_did, AirdropV4, DidMintAccounts, MetaplexAccounts};
+use crate::state::*;
+
+/// Operator-signed mint authorization, Borsh-serialized as the Ed25519 message
+/// `project` pins the voucher to one collection so it can't be replayed elsewhere
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintVoucher {
+    pub project: Pubkey,
+    pub order_id: String,
+    pub wallet: Pubkey,
+    pub merkle_root: String,
+    pub expiry: i64,            // Unix timestamp, voucher is rejected after this
+}
+
+#[event]
+pub struct ClaimDidV4 {
+    pub order_id: String,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    pub voucher_signer: Pubkey,
+}
+
+/// User mints their own DID with an operator voucher, paying all rent themselves
+/// The preceding instruction must be an Ed25519 precompile check of the voucher
+#[derive(Accounts)]
+#[instruction(order_id: String, merkle_root: String)]
+pub struct ClaimDid<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = !non_transferable_project.is_paused(pause_flags::USER) @ crate::error::ErrorCode::ProgramPaused,
+        // The voucher is an operator mint, so an operator pause stops claims too
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        constraint = non_transferable_project.issuance_mode == IssuanceMode::Token2022 @ crate::error::ErrorCode::WrongIssuanceMode
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the voucher signer MINT (not needed for admin / legacy operators)
+    pub voucher_signer_role: Option<Account<'info, RoleAccount>>,
+
+    /// Paid order created by authorize_mint, must belong to the claiming wallet
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &hash_seed(&order_id)],
+        bump = order.bump,
+        constraint = order.status == OrderStatus::Pending @ crate::error::ErrorCode::OrderNotPending,
+        constraint = order.wallet == user.key() @ crate::error::ErrorCode::OrderWalletMismatch
+    )]
+    pub order: Box<Account<'info, OrderAccount>>,
+
+    /// SPL orders only: payment mint config the order's escrow is counted on
+    #[account(
+        mut,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Option<Box<Account<'info, PaymentMintAccount>>>,
+
+    /// Wallet -> DID mint index (one DID per wallet)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = WALLET_INDEX_SIZE,
+        seeds = [WALLET_INDEX_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = wallet_index.mint == Pubkey::default() @ crate::error::ErrorCode::AlreadyMinted
+    )]
+    pub wallet_index: Box<Account<'info, WalletIndex>>,
+
+    /// CHECK: Blacklist entry PDA for the wallet - must not exist
+    #[account(
+        seeds = [BLOCKED_WALLET_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = wallet_blacklist.data_is_empty() @ crate::error::ErrorCode::WalletBlacklisted
+    )]
+    pub wallet_blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Blacklist entry PDA for the LDID (merkle root) - must not exist
+    #[account(
+        seeds = [BLOCKED_LDID_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &ldid_seed(&merkle_root)],
+        bump,
+        constraint = ldid_blacklist.data_is_empty() @ crate::error::ErrorCode::LdidBlacklisted
+    )]
+    pub ldid_blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Token-2022 NFT Mint account, verified against the project + order_id PDA in mint_did
+    #[account(mut)]
+    pub non_transferable_nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: User's Token-2022 ATA
+    #[account(mut)]
+    pub user_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Collection Mint (Group)
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(non_transferable_project.project_id, &non_transferable_project.key())],
+        bump = non_transferable_project.mint_bump,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, used to read the Ed25519 precompile instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex metadata PDA of the DID mint (Metaplex projects only), created by Token Metadata
+    #[account(mut)]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition PDA of the DID mint (Metaplex projects only), created by Token Metadata
+    #[account(mut)]
+    pub nft_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex metadata PDA of the collection mint, checked by Token Metadata
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition PDA of the collection mint, checked by Token Metadata
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ClaimDid>,
+    order_id: String,
+    merkle_root: String,
+    expiry: i64,
+) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp <= expiry,
+        crate::error::ErrorCode::VoucherExpired
+    );
+
+    let wallet = ctx.accounts.user.key();
+    let voucher = MintVoucher {
+        project: ctx.accounts.non_transferable_project.key(),
+        order_id: order_id.clone(),
+        wallet,
+        merkle_root: merkle_root.clone(),
+        expiry,
+    };
+    let mut message = Vec::new();
+    voucher
+        .serialize(&mut message)
+        .map_err(|_| crate::error::ErrorCode::InvalidDataProvided)?;
+
+    // The precompile already checked the signature, we only bind it to this voucher and an approved key
+    let voucher_signer =
+        crate::utils::verify_ed25519_ix(&ctx.accounts.instructions.to_account_info(), &message)?;
+    require!(
+        ctx.accounts.non_transferable_project.has_permission(
+            &voucher_signer,
+            ctx.accounts.voucher_signer_role.as_deref(),
+            permissions::MINT
+        ),
+        crate::error::ErrorCode::SignatureVerificationFailed
+    );
+
+    let merkle_root = crate::utils::canonical_hex32(&merkle_root)?;
+
+    mint_did(
+        &DidMintAccounts {
+            payer: &ctx.accounts.user.to_account_info(),
+            project: &ctx.accounts.non_transferable_project,
+            nft_mint: &ctx.accounts.non_transferable_nft_mint.to_account_info(),
+            user_account: &ctx.accounts.user.to_account_info(),
+            user_token_account: &ctx.accounts.user_token_account.to_account_info(),
+            collection_mint: &ctx.accounts.collection_mint.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            program_id: ctx.program_id,
+            metaplex: MetaplexAccounts::from_optional(
+                ctx.accounts.nft_metadata.as_deref(),
+                ctx.accounts.nft_master_edition.as_deref(),
+                ctx.accounts.collection_metadata.as_deref(),
+                ctx.accounts.collection_master_edition.as_deref(),
+                ctx.accounts.token_metadata_program.as_deref(),
+                Some(ctx.accounts.instructions.as_ref()),
+            ),
+        },
+        &order_id,
+        wallet,
+        &merkle_root,
+    )?;
+
+    let accounts = &mut *ctx.accounts;
+    accounts.order.release_escrow(
+        &mut accounts.non_transferable_project,
+        accounts.payment_mint_config.as_deref_mut().map(|config| &mut **config),
+    )?;
+    accounts.order.status = OrderStatus::Fulfilled;
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.total_minted = project.total_minted.checked_add(1).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    let wallet_index = &mut ctx.accounts.wallet_index;
+    wallet_index.mint = ctx.accounts.non_transferable_nft_mint.key();
+    wallet_index.bump = ctx.bumps.wallet_index;
+
+    msg!(
+        "ClaimDidV4:orderId:{};wallet:{};mint:{};voucherSigner:{};",
+        order_id,
+        wallet,
+        ctx.accounts.non_transferable_nft_mint.key(),
+        voucher_signer
+    );
+
+    // Same AirdropV4 as operator mints so indexers see every DID the same way
+    emit!(AirdropV4 {
+        order_id: order_id.clone(),
+        wallet,
+        mint: ctx.accounts.non_transferable_nft_mint.key(),
+        merkle_root,
+    });
+
+    emit!(ClaimDidV4 {
+        order_id,
+        wallet,
+        mint: ctx.accounts.non_transferable_nft_mint.key(),
+        voucher_signer,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_interface::TokenInterface;
+use spl_token_group_interface::instruction::update_group_max_size;
+
+use crate::state::*;
+
+#[event]
+pub struct CollectionMaxSizeV4 {
+    pub collection_mint: Pubkey,
+    pub max_size: u64,
+}
+
+/// Admin updates the collection group's max_size (supply cap)
+#[derive(Accounts)]
+pub struct SetCollectionMaxSize<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// CHECK: Collection Mint (Group)
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(non_transferable_project.project_id, &non_transferable_project.key())],
+        bump = non_transferable_project.mint_bump,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn set_max_size_handler(ctx: Context<SetCollectionMaxSize>, max_size: u64) -> Result<()> {
+    let project_id_bytes = project_id_seed(ctx.accounts.non_transferable_project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[ctx.accounts.non_transferable_project.bump],
+    ];
+
+    // Token-2022 rejects a max_size below the current group size
+    invoke_signed(
+        &update_group_max_size(
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.collection_mint.key(),
+            &ctx.accounts.non_transferable_project.key(),
+            max_size,
+        ),
+        &[
+            ctx.accounts.collection_mint.to_account_info(),
+            ctx.accounts.non_transferable_project.to_account_info(),
+        ],
+        &[project_signer_seeds],
+    )?;
+
+    msg!(
+        "CollectionMaxSizeV4:collectionMint:{};maxSize:{};",
+        ctx.accounts.collection_mint.key(),
+        max_size
+    );
+
+    emit!(CollectionMaxSizeV4 {
+        collection_mint: ctx.accounts.collection_mint.key(),
+        max_size,
+    });
+
+    Ok(())
+}
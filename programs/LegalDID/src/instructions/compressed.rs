@@ -0,0 +1,479 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::accounts::TreeConfig;
+use mpl_bubblegum::instructions::{
+    BurnCpiBuilder, CreateTreeConfigCpiBuilder, MintToCollectionV1CpiBuilder, MintV1CpiBuilder,
+};
+use mpl_bubblegum::programs::MPL_BUBBLEGUM_ID;
+use mpl_bubblegum::types::{Collection, MetadataArgs, TokenProgramVersion, TokenStandard};
+use mpl_bubblegum::utils::get_asset_id;
+
+use crate::instructions::airdrop::AirdropV4;
+use crate::instructions::burn::BurnV4;
+use crate::state::*;
+
+/// SPL account compression / noop programs used by Bubblegum's V1 instructions
+const SPL_ACCOUNT_COMPRESSION_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+const SPL_NOOP_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Bubblegum PDA that signs Token Metadata collection CPIs
+const BUBBLEGUM_COLLECTION_CPI_PREFIX: &[u8] = b"collection_cpi";
+
+#[event]
+pub struct CompressedTreeV4 {
+    pub merkle_tree: Pubkey,
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+}
+
+/// Admin creates the Bubblegum tree config for a pre-allocated merkle tree and
+/// switches the project to compressed issuance, only once per project
+/// The tree creator is the project PDA, so only this program can mint into it
+#[derive(Accounts)]
+pub struct InitializeCompressedTree<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized,
+        // A project that already issued Token-2022 DIDs can't switch mode
+        constraint = non_transferable_project.total_minted == 0 @ crate::error::ErrorCode::WrongIssuanceMode,
+        // Replacing the tree would leave every DID in the old one unburnable
+        constraint = non_transferable_project.merkle_tree.is_none() @ crate::error::ErrorCode::MerkleTreeAlreadyInitialized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// CHECK: Bubblegum tree config PDA, created by Bubblegum
+    #[account(mut)]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Concurrent merkle tree, allocated by the client and owned by spl-account-compression
+    #[account(mut, owner = SPL_ACCOUNT_COMPRESSION_ID @ crate::error::ErrorCode::InvalidMerkleTree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(address = MPL_BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program (Bubblegum log wrapper)
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Mint a compressed DID for a wallet
+/// The leaf owner and delegate are the wallet's index PDA, so only this program can
+/// sign for the leaf - Bubblegum transfer / delegate can't move it, which keeps it soulbound
+#[derive(Accounts)]
+#[instruction(order_id: String, wallet: Pubkey, merkle_root: String)]
+pub struct AirdropCompressed<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::MINT) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        constraint = non_transferable_project.issuance_mode == IssuanceMode::Compressed @ crate::error::ErrorCode::WrongIssuanceMode,
+        constraint = non_transferable_project.merkle_tree == Some(merkle_tree.key()) @ crate::error::ErrorCode::InvalidMerkleTree,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    /// Paid order created by authorize_mint
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &hash_seed(&order_id)],
+        bump = order.bump,
+        constraint = order.status == OrderStatus::Pending @ crate::error::ErrorCode::OrderNotPending,
+        constraint = order.wallet == wallet @ crate::error::ErrorCode::OrderWalletMismatch
+    )]
+    pub order: Box<Account<'info, OrderAccount>>,
+
+    /// SPL orders only: payment mint config the order's escrow is counted on
+    #[account(
+        mut,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Option<Box<Account<'info, PaymentMintAccount>>>,
+
+    /// Wallet -> DID asset id index (one DID per wallet), also the leaf owner
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = WALLET_INDEX_SIZE,
+        seeds = [WALLET_INDEX_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump,
+        constraint = wallet_index.mint == Pubkey::default() @ crate::error::ErrorCode::AlreadyMinted
+    )]
+    pub wallet_index: Box<Account<'info, WalletIndex>>,
+
+    /// CHECK: Blacklist entry PDA for the wallet - must not exist
+    #[account(
+        seeds = [BLOCKED_WALLET_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.as_ref()],
+        bump,
+        constraint = wallet_blacklist.data_is_empty() @ crate::error::ErrorCode::WalletBlacklisted
+    )]
+    pub wallet_blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: Blacklist entry PDA for the LDID (merkle root) - must not exist
+    #[account(
+        seeds = [BLOCKED_LDID_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &ldid_seed(&merkle_root)],
+        bump,
+        constraint = ldid_blacklist.data_is_empty() @ crate::error::ErrorCode::LdidBlacklisted
+    )]
+    pub ldid_blacklist: UncheckedAccount<'info>,
+
+    /// CHECK: DID holder, owns the leaf through its wallet index
+    #[account(address = wallet @ crate::error::ErrorCode::OrderWalletMismatch)]
+    pub user_account: UncheckedAccount<'info>,
+
+    /// CHECK: Collection Mint, recorded as the DID's collection
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(non_transferable_project.project_id, &non_transferable_project.key())],
+        bump = non_transferable_project.mint_bump,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex metadata PDA of the collection mint (Metaplex projects only), checked by Token Metadata
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition PDA of the collection mint, checked by Token Metadata
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Bubblegum collection CPI signer
+    #[account(seeds = [BUBBLEGUM_COLLECTION_CPI_PREFIX], seeds::program = MPL_BUBBLEGUM_ID, bump)]
+    pub bubblegum_signer: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Bubblegum tree config PDA, validated by Bubblegum
+    #[account(mut)]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Project merkle tree, checked against the project above
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(address = MPL_BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program (Bubblegum log wrapper)
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Holder burns their compressed DID, proof nodes come through remaining_accounts
+#[derive(Accounts)]
+pub struct BurnCompressed<'info> {
+    pub nft_owner: Signer<'info>,
+
+    /// Authority account to receive wallet index rent - must be project authority
+    #[account(
+        mut,
+        constraint = authority.key() == non_transferable_project.authority @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub authority: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.merkle_tree == Some(merkle_tree.key()) @ crate::error::ErrorCode::InvalidMerkleTree
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Wallet index, signs for the leaf as its owner and is then closed so the wallet
+    /// can be re-issued, rent back to admin
+    #[account(
+        mut,
+        close = authority,
+        seeds = [WALLET_INDEX_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), nft_owner.key().as_ref()],
+        bump = wallet_index.bump
+    )]
+    pub wallet_index: Box<Account<'info, WalletIndex>>,
+
+    /// CHECK: Bubblegum tree config PDA, validated by Bubblegum
+    #[account(mut)]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: Project merkle tree, checked against the project above
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program
+    #[account(address = MPL_BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program (Bubblegum log wrapper)
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_tree_handler(
+    ctx: Context<InitializeCompressedTree>,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<()> {
+    let project = &ctx.accounts.non_transferable_project;
+    let project_id_bytes = project_id_seed(project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[project.bump],
+    ];
+
+    let project_info = project.to_account_info();
+    CreateTreeConfigCpiBuilder::new(&ctx.accounts.bubblegum_program)
+        .tree_config(&ctx.accounts.tree_config)
+        .merkle_tree(&ctx.accounts.merkle_tree)
+        .payer(&ctx.accounts.authority)
+        .tree_creator(&project_info)
+        .log_wrapper(&ctx.accounts.log_wrapper)
+        .compression_program(&ctx.accounts.compression_program)
+        .system_program(&ctx.accounts.system_program)
+        .max_depth(max_depth)
+        .max_buffer_size(max_buffer_size)
+        .public(false)
+        .invoke_signed(&[project_signer_seeds])?;
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.issuance_mode = IssuanceMode::Compressed;
+    project.merkle_tree = Some(ctx.accounts.merkle_tree.key());
+
+    msg!(
+        "CompressedTreeV4:merkleTree:{};maxDepth:{};maxBufferSize:{};",
+        ctx.accounts.merkle_tree.key(),
+        max_depth,
+        max_buffer_size
+    );
+
+    emit!(CompressedTreeV4 {
+        merkle_tree: ctx.accounts.merkle_tree.key(),
+        max_depth,
+        max_buffer_size,
+    });
+
+    Ok(())
+}
+
+pub fn airdrop_handler(
+    ctx: Context<AirdropCompressed>,
+    order_id: String,
+    wallet: Pubkey,
+    merkle_root: String,
+) -> Result<()> {
+    // merkle_root must be exactly 32 bytes of hex, stored in canonical form
+    let merkle_root = crate::utils::canonical_hex32(&merkle_root)?;
+
+    // The next leaf's nonce is the tree's mint count, which fixes the asset id
+    let nonce = {
+        let data = ctx.accounts.tree_config.try_borrow_data()?;
+        TreeConfig::from_bytes(&data)
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?
+            .num_minted
+    };
+    let asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+
+    let project = &ctx.accounts.non_transferable_project;
+    let project_id_bytes = project_id_seed(project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[project.bump],
+    ];
+
+    // Same metadata as the Token-2022 DID: name cut to Bubblegum's limit, merkle_root in
+    // the uri and the project collection
+    let name: String = format!("LDID #{}", order_id)
+        .chars()
+        .take(METAPLEX_MAX_NAME_LEN)
+        .collect();
+    let metadata = MetadataArgs {
+        name,
+        symbol: project.symbol.clone(),
+        uri: format!("{}{}.json", project.base_uri, merkle_root),
+        seller_fee_basis_points: 0,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: Some(Collection {
+            verified: false,
+            key: ctx.accounts.collection_mint.key(),
+        }),
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: vec![],
+    };
+
+    let project_info = project.to_account_info();
+    let leaf_owner = ctx.accounts.wallet_index.to_account_info();
+    if project.metaplex_metadata {
+        let (
+            Some(collection_metadata),
+            Some(collection_master_edition),
+            Some(bubblegum_signer),
+            Some(token_metadata_program),
+        ) = (
+            ctx.accounts.collection_metadata.as_deref(),
+            ctx.accounts.collection_master_edition.as_deref(),
+            ctx.accounts.bubblegum_signer.as_deref(),
+            ctx.accounts.token_metadata_program.as_deref(),
+        )
+        else {
+            return err!(crate::error::ErrorCode::MetaplexAccountsMissing);
+        };
+
+        // The collection has Metaplex metadata, mint straight into it as a verified member
+        MintToCollectionV1CpiBuilder::new(&ctx.accounts.bubblegum_program)
+            .tree_config(&ctx.accounts.tree_config)
+            .leaf_owner(&leaf_owner)
+            .leaf_delegate(&leaf_owner)
+            .merkle_tree(&ctx.accounts.merkle_tree)
+            .payer(&ctx.accounts.authority)
+            .tree_creator_or_delegate(&project_info)
+            .collection_authority(&project_info)
+            .collection_mint(&ctx.accounts.collection_mint)
+            .collection_metadata(collection_metadata)
+            .collection_edition(collection_master_edition)
+            .bubblegum_signer(bubblegum_signer)
+            .log_wrapper(&ctx.accounts.log_wrapper)
+            .compression_program(&ctx.accounts.compression_program)
+            .token_metadata_program(token_metadata_program)
+            .system_program(&ctx.accounts.system_program)
+            .metadata(metadata)
+            .invoke_signed(&[project_signer_seeds])?;
+    } else {
+        MintV1CpiBuilder::new(&ctx.accounts.bubblegum_program)
+            .tree_config(&ctx.accounts.tree_config)
+            .leaf_owner(&leaf_owner)
+            .leaf_delegate(&leaf_owner)
+            .merkle_tree(&ctx.accounts.merkle_tree)
+            .payer(&ctx.accounts.authority)
+            .tree_creator_or_delegate(&project_info)
+            .log_wrapper(&ctx.accounts.log_wrapper)
+            .compression_program(&ctx.accounts.compression_program)
+            .system_program(&ctx.accounts.system_program)
+            .metadata(metadata)
+            .invoke_signed(&[project_signer_seeds])?;
+    }
+
+    let accounts = &mut *ctx.accounts;
+    accounts.order.release_escrow(
+        &mut accounts.non_transferable_project,
+        accounts.payment_mint_config.as_deref_mut().map(|config| &mut **config),
+    )?;
+    accounts.order.status = OrderStatus::Fulfilled;
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.total_minted = project.total_minted.checked_add(1).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    let wallet_index = &mut ctx.accounts.wallet_index;
+    wallet_index.mint = asset_id;
+    wallet_index.bump = ctx.bumps.wallet_index;
+
+    msg!(
+        "AirdropV4:orderId:{};wallet:{};mint:{};merkleRoot:{};",
+        order_id,
+        wallet,
+        asset_id,
+        merkle_root
+    );
+
+    emit!(AirdropV4 {
+        order_id,
+        wallet,
+        mint: asset_id,
+        merkle_root,
+    });
+
+    Ok(())
+}
+
+pub fn burn_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BurnCompressed<'info>>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    index: u32,
+) -> Result<()> {
+    let asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+    require!(
+        ctx.accounts.wallet_index.mint == asset_id,
+        crate::error::ErrorCode::InvalidMintAccount
+    );
+
+    let owner_key = ctx.accounts.nft_owner.key();
+    let project_key = ctx.accounts.non_transferable_project.key();
+    let wallet_index_signer_seeds: &[&[u8]] = &[
+        WALLET_INDEX_PREFIX.as_bytes(),
+        project_key.as_ref(),
+        owner_key.as_ref(),
+        &[ctx.accounts.wallet_index.bump],
+    ];
+    let leaf_owner = ctx.accounts.wallet_index.to_account_info();
+
+    let proof: Vec<(&AccountInfo<'info>, bool, bool)> = ctx
+        .remaining_accounts
+        .iter()
+        .map(|node| (node, false, false))
+        .collect();
+
+    BurnCpiBuilder::new(&ctx.accounts.bubblegum_program)
+        .tree_config(&ctx.accounts.tree_config)
+        .leaf_owner(&leaf_owner, true)
+        .leaf_delegate(&leaf_owner, false)
+        .merkle_tree(&ctx.accounts.merkle_tree)
+        .log_wrapper(&ctx.accounts.log_wrapper)
+        .compression_program(&ctx.accounts.compression_program)
+        .system_program(&ctx.accounts.system_program)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .add_remaining_accounts(&proof)
+        .invoke_signed(&[wallet_index_signer_seeds])?;
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.total_burned = project.total_burned.checked_add(1).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    msg!("BurnV4:wallet:{};mint:{};", owner_key, asset_id);
+
+    emit!(BurnV4 {
+        wallet: owner_key,
+        mint: asset_id,
+    });
+
+    Ok(())
+}
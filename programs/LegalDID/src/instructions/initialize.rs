@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
 use anchor_spl::token_interface::TokenInterface;
+use mpl_token_metadata::instructions::CreateV1CpiBuilder;
+use mpl_token_metadata::types::{CollectionDetails, PrintSupply, TokenStandard};
 use spl_token_2022::extension::group_pointer::instruction::initialize as init_group_pointer;
 use spl_token_2022::extension::metadata_pointer::instruction::initialize as init_metadata_pointer;
 use spl_token_2022::extension::ExtensionType;
@@ -12,9 +15,11 @@ use crate::state::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct InitializeArgs {
+    pub project_id: u64,
     pub name: String,
     pub symbol: String,
     pub base_uri: String,
+    pub metaplex_metadata: bool, // Also create Metaplex metadata so DIDs show up in Metaplex-only wallets
 }
 
 #[derive(Accounts)]
@@ -26,20 +31,51 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = NON_TRANSFERABLE_PROJECT_SIZE,
-        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes()],
+        space = ProjectAccount::space_for(args.base_uri.len()),
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(args.project_id)],
         bump
     )]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 
+    /// CHECK: Legacy project 0 - its authority may create other project ids, read in the handler
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(LEGACY_PROJECT_ID)],
+        bump
+    )]
+    pub legacy_project: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: This program's ProgramData - its upgrade authority may create other project ids, read in the handler
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = ProgramData::owner()
+    )]
+    pub program_data: Option<UncheckedAccount<'info>>,
+
     /// CHECK: Token-2022 Mint account, requires manual extension initialization
     #[account(
         mut,
-        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes()],
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(args.project_id, &non_transferable_project.key())],
         bump,
     )]
     pub non_transferable_project_mint: UncheckedAccount<'info>,
 
+    /// CHECK: Metaplex metadata PDA of the collection mint (metaplex_metadata only), created by Token Metadata
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition PDA of the collection mint (metaplex_metadata only), created by Token Metadata
+    #[account(mut)]
+    pub collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, required by Token Metadata
+    #[account(address = instructions_sysvar::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
     /// Token-2022 program
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -47,6 +83,25 @@ pub struct Initialize<'info> {
 }
 
 pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
+    require!(args.name.len() <= MAX_NAME_LEN, crate::error::ErrorCode::NameTooLong);
+    require!(args.symbol.len() <= MAX_SYMBOL_LEN, crate::error::ErrorCode::SymbolTooLong);
+    require!(args.base_uri.len() <= MAX_BASE_URI_LEN, crate::error::ErrorCode::BaseUriTooLong);
+    if args.metaplex_metadata {
+        // Metaplex metadata has much tighter limits than Token-2022
+        require!(args.name.len() <= METAPLEX_MAX_NAME_LEN, crate::error::ErrorCode::NameTooLong);
+        require!(args.symbol.len() <= METAPLEX_MAX_SYMBOL_LEN, crate::error::ErrorCode::SymbolTooLong);
+        require!(args.base_uri.len() <= METAPLEX_MAX_BASE_URI_LEN, crate::error::ErrorCode::BaseUriTooLong);
+    }
+
+    // Project 0 is the legacy singleton, any other id is created by the legacy project
+    // authority or the upgrade authority so nobody can squat ids under the official program
+    if args.project_id != LEGACY_PROJECT_ID {
+        require!(
+            can_create_projects(ctx.accounts, &ctx.accounts.authority.key())?,
+            crate::error::ErrorCode::Unauthorized
+        );
+    }
+
     let non_transferable_project = &mut ctx.accounts.non_transferable_project;
 
     non_transferable_project.mint_price = 100;
@@ -54,11 +109,25 @@ pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
     non_transferable_project.destination = ctx.accounts.authority.key(); // Default to authority
     non_transferable_project.bump = ctx.bumps.non_transferable_project;
     non_transferable_project.mint_bump = ctx.bumps.non_transferable_project_mint;
+    non_transferable_project.project_id = args.project_id;
 
     non_transferable_project.name = args.name.clone();
     non_transferable_project.symbol = args.symbol.clone();
     non_transferable_project.base_uri = args.base_uri.clone();
     non_transferable_project.operators = vec![]; // Initialize empty operator list
+    non_transferable_project.reclaim_timeout_slots = DEFAULT_RECLAIM_TIMEOUT_SLOTS;
+    non_transferable_project.pending_authority = None;
+    non_transferable_project.revenue_shares = vec![]; // Everything goes to destination until a split is set
+    non_transferable_project.guardian = None;
+    non_transferable_project.paused = 0;
+    non_transferable_project.total_minted = 0;
+    non_transferable_project.total_burned = 0;
+    non_transferable_project.total_revoked = 0;
+    non_transferable_project.total_fees_collected = 0;
+    non_transferable_project.version = PROJECT_ACCOUNT_VERSION;
+    non_transferable_project.issuance_mode = IssuanceMode::Token2022; // initialize_compressed_tree switches to Compressed
+    non_transferable_project.merkle_tree = None;
+    non_transferable_project.metaplex_metadata = args.metaplex_metadata;
 
     // Calculate Token-2022 Mint required space (with extensions)
     // Add GroupPointer + MetadataPointer extensions for Collection functionality
@@ -86,14 +155,18 @@ pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
     
     msg!("Collection Mint space: base={}, group={}, total={}", base_space, group_space, mint_space);
 
+    let project_scope = project_scope_seed(args.project_id, &ctx.accounts.non_transferable_project.key());
     let mint_signer_seeds: &[&[u8]] = &[
         NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(),
-        &[non_transferable_project.mint_bump],
+        &project_scope,
+        &[ctx.accounts.non_transferable_project.mint_bump],
     ];
 
+    let project_id_bytes = project_id_seed(args.project_id);
     let project_signer_seeds: &[&[u8]] = &[
         NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
-        &[non_transferable_project.bump],
+        &project_id_bytes,
+        &[ctx.accounts.non_transferable_project.bump],
     ];
 
     // 1. Create Mint account (use base_space first, metadata and group will auto realloc)
@@ -234,7 +307,51 @@ pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
         &[project_signer_seeds],
     )?;
 
+    // 9. Metaplex collection NFT, DIDs are verified into it by airdrop / claim_did
+    if args.metaplex_metadata {
+        let (
+            Some(collection_metadata),
+            Some(collection_master_edition),
+            Some(token_metadata_program),
+            Some(sysvar_instructions),
+        ) = (
+            ctx.accounts.collection_metadata.as_deref(),
+            ctx.accounts.collection_master_edition.as_deref(),
+            ctx.accounts.token_metadata_program.as_deref(),
+            ctx.accounts.sysvar_instructions.as_deref(),
+        )
+        else {
+            return err!(crate::error::ErrorCode::MetaplexAccountsMissing);
+        };
+
+        let project_info = ctx.accounts.non_transferable_project.to_account_info();
+        let mint_info = ctx.accounts.non_transferable_project_mint.to_account_info();
+
+        CreateV1CpiBuilder::new(token_metadata_program)
+            .metadata(collection_metadata)
+            .master_edition(Some(collection_master_edition))
+            .mint(&mint_info, false)
+            .authority(&project_info)
+            .payer(&ctx.accounts.authority.to_account_info())
+            .update_authority(&project_info, true)
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .sysvar_instructions(sysvar_instructions)
+            .spl_token_program(Some(&ctx.accounts.token_program.to_account_info()))
+            .name(args.name.clone())
+            .symbol(args.symbol.clone())
+            .uri(args.base_uri.clone())
+            .seller_fee_basis_points(0)
+            .is_mutable(true)
+            .token_standard(TokenStandard::NonFungible)
+            .collection_details(CollectionDetails::V1 { size: 0 })
+            .print_supply(PrintSupply::Zero)
+            .invoke_signed(&[project_signer_seeds])?;
+
+        msg!("Metaplex collection metadata: {}", collection_metadata.key());
+    }
+
     msg!("Project initialized with Token-2022");
+    msg!("Project ID: {}", args.project_id);
     msg!("Name: {}", args.name);
     msg!("Symbol: {}", args.symbol);
     msg!("Base URI: {}", args.base_uri);
@@ -243,3 +360,31 @@ pub fn handler(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// Whether `key` is the legacy project authority or the program upgrade authority
+fn can_create_projects(accounts: &Initialize, key: &Pubkey) -> Result<bool> {
+    if let Some(legacy_project) = &accounts.legacy_project {
+        if legacy_project.owner == &crate::ID && !legacy_project.data_is_empty() {
+            let data = legacy_project.try_borrow_data()?;
+            // An unmigrated legacy layout falls through to the upgrade authority
+            if let Ok(project) = ProjectAccount::try_deserialize(&mut &data[..]) {
+                if project.authority == *key {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    if let Some(program_data) = &accounts.program_data {
+        if *program_data.owner == ProgramData::owner() {
+            let data = program_data.try_borrow_data()?;
+            if let Ok(program_data) = ProgramData::try_deserialize(&mut &data[..]) {
+                if program_data.upgrade_authority_address == Some(*key) {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::state::*;
+
+#[event]
+pub struct ProjectMigratedV4 {
+    pub project: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+/// Upgrade a ProjectAccount in place to the current layout (admin only)
+/// Keeps the same PDA, so the collection mint and issued DIDs stay attached
+/// The pre multi-project singleton is migrated as project 0 (LEGACY_PROJECT_ID)
+#[derive(Accounts)]
+#[instruction(project_id: u64)]
+pub struct MigrateProject<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Deserialized by hand - an older layout can't load as Account<ProjectAccount>
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(project_id)],
+        bump
+    )]
+    pub non_transferable_project: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateProject>, project_id: u64) -> Result<()> {
+    let project_info = ctx.accounts.non_transferable_project.to_account_info();
+
+    // Accounts allocated before `version` existed are shorter than the v1 size,
+    // from v1 on the version byte sits at the end of the v1 prefix and later
+    // fields are appended after it, one group per version
+    let (mut project, from_version) = {
+        let data = project_info.try_borrow_data()?;
+        require!(
+            data.starts_with(ProjectAccount::DISCRIMINATOR),
+            crate::error::ErrorCode::InvalidAccountData
+        );
+
+        let mut rest: &[u8] = &data[8..];
+        if data.len() < NON_TRANSFERABLE_PROJECT_SIZE_V1 {
+            require!(
+                project_id == LEGACY_PROJECT_ID,
+                crate::error::ErrorCode::InvalidAccountData
+            );
+            let old = ProjectAccountV0::deserialize(&mut rest)
+                .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+            (ProjectAccount::from(old), 0)
+        } else {
+            let old = ProjectAccountV1::deserialize(&mut rest)
+                .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+            let version = old.version;
+            let mut project = ProjectAccount::from(old);
+
+            if version >= 2 {
+                project.issuance_mode = IssuanceMode::deserialize(&mut rest)
+                    .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+                project.merkle_tree = Option::<Pubkey>::deserialize(&mut rest)
+                    .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+            }
+            if version >= 3 {
+                project.metaplex_metadata = bool::deserialize(&mut rest)
+                    .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+            }
+            if version >= 4 {
+                project.pending_escrow = u64::deserialize(&mut rest)
+                    .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+            }
+
+            (project, version)
+        }
+    };
+
+    require!(
+        project.authority == ctx.accounts.authority.key(),
+        crate::error::ErrorCode::Unauthorized
+    );
+    require!(
+        from_version < PROJECT_ACCOUNT_VERSION,
+        crate::error::ErrorCode::AlreadyMigrated
+    );
+
+    // Defaults for fields added since `from_version`
+    // Accounts from before the reclaim timeout read 0, which would let a payer reclaim right after paying
+    // pending_escrow starts at 0, orders paid before it was tracked are not held back from withdraw
+    if project.reclaim_timeout_slots == 0 {
+        project.reclaim_timeout_slots = DEFAULT_RECLAIM_TIMEOUT_SLOTS;
+    }
+    project.version = PROJECT_ACCOUNT_VERSION;
+
+    let new_len = ProjectAccount::space_for(project.base_uri.len());
+    if project_info.data_len() < new_len {
+        let rent = Rent::get()?;
+        let top_up = rent
+            .minimum_balance(new_len)
+            .saturating_sub(project_info.lamports());
+
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: project_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+
+        project_info.resize(new_len)?;
+    }
+
+    // Rewrite the whole account so no bytes from the old layout are left behind
+    let mut data = project_info.try_borrow_mut_data()?;
+    data.fill(0);
+    let mut writer: &mut [u8] = &mut data;
+    project.try_serialize(&mut writer)?;
+
+    msg!(
+        "ProjectMigratedV4:project:{};fromVersion:{};toVersion:{};",
+        project_info.key(),
+        from_version,
+        PROJECT_ACCOUNT_VERSION
+    );
+
+    emit!(ProjectMigratedV4 {
+        project: project_info.key(),
+        from_version,
+        to_version: PROJECT_ACCOUNT_VERSION,
+    });
+
+    Ok(())
+}
@@ -1,13 +1,45 @@
 #![allow(ambiguous_glob_reexports)]
 
 pub mod airdrop;
+pub mod airdrop_batch;
 pub mod authorize_mint;
+pub mod authorize_mint_spl;
+pub mod blacklist;
 pub mod burn;
+pub mod claim_did;
+pub mod collection;
+pub mod compressed;
 pub mod initialize;
+pub mod migrate;
+pub mod pause;
+pub mod payment_mint;
+pub mod refund;
+pub mod revoke;
+pub mod role;
+pub mod set_base_uri;
+pub mod transfer_authority;
+pub mod update_did;
 pub mod withdraw;
+pub mod withdraw_spl;
 
 pub use airdrop::*;
+pub use airdrop_batch::*;
 pub use authorize_mint::*;
+pub use authorize_mint_spl::*;
+pub use blacklist::*;
 pub use burn::*;
+pub use claim_did::*;
+pub use collection::*;
+pub use compressed::*;
 pub use initialize::*;
+pub use migrate::*;
+pub use pause::*;
+pub use payment_mint::*;
+pub use refund::*;
+pub use revoke::*;
+pub use role::*;
+pub use set_base_uri::*;
+pub use transfer_authority::*;
+pub use update_did::*;
 pub use withdraw::*;
+pub use withdraw_spl::*;
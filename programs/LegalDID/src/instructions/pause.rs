@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct GuardianSetV4 {
+    pub guardian: Option<Pubkey>,
+}
+
+#[event]
+pub struct PauseChangedV4 {
+    pub paused: u8,
+    pub changed_by: Pubkey,
+}
+
+/// Set or clear the guardian key (admin only)
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+/// Change pause flags (admin, or guardian for pausing only)
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.is_admin_or_guardian(&authority.key()) @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn set_guardian_handler(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+    ctx.accounts.non_transferable_project.guardian = guardian;
+
+    emit!(GuardianSetV4 { guardian });
+
+    msg!("SetGuardian:guardian:{:?}", guardian);
+
+    Ok(())
+}
+
+pub fn set_paused_handler(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+    let project = &mut ctx.accounts.non_transferable_project;
+
+    require!(
+        paused & !pause_flags::ALL == 0,
+        crate::error::ErrorCode::InvalidPauseFlags
+    );
+
+    // A leaked guardian key must not be able to undo an admin pause
+    if !project.is_admin(&ctx.accounts.authority.key()) {
+        require!(
+            paused & project.paused == project.paused,
+            crate::error::ErrorCode::InvalidPauseFlags
+        );
+    }
+
+    project.paused = paused;
+
+    emit!(PauseChangedV4 {
+        paused,
+        changed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("SetPaused:paused:{};by:{}", paused, ctx.accounts.authority.key());
+
+    Ok(())
+}
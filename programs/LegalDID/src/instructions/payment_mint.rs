@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::*;
+
+#[event]
+pub struct SetPaymentMintV4 {
+    pub mint: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct RemovePaymentMintV4 {
+    pub mint: Pubkey,
+}
+
+/// Admin or SET_PRICE role holder can add a payment mint or change its price
+#[derive(Accounts)]
+pub struct SetPaymentMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::SET_PRICE) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer SET_PRICE (not needed for admin)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PAYMENT_MINT_SIZE,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint.key().as_ref()],
+        bump
+    )]
+    pub payment_mint_config: Box<Account<'info, PaymentMintAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove a payment mint (admin only)
+#[derive(Accounts)]
+pub struct RemovePaymentMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump,
+        constraint = payment_mint_config.pending_escrow == 0 @ crate::error::ErrorCode::EscrowPending
+    )]
+    pub payment_mint_config: Box<Account<'info, PaymentMintAccount>>,
+}
+
+pub fn set_handler(ctx: Context<SetPaymentMint>, price: u64) -> Result<()> {
+    let config = &mut ctx.accounts.payment_mint_config;
+    config.mint = ctx.accounts.payment_mint.key();
+    config.price = price;
+    config.bump = ctx.bumps.payment_mint_config;
+
+    msg!("SetPaymentMintV4:mint:{};price:{};", config.mint, price);
+
+    emit!(SetPaymentMintV4 {
+        mint: config.mint,
+        price,
+    });
+
+    Ok(())
+}
+
+pub fn remove_handler(ctx: Context<RemovePaymentMint>) -> Result<()> {
+    let mint = ctx.accounts.payment_mint_config.mint;
+
+    msg!("RemovePaymentMintV4:mint:{};", mint);
+
+    emit!(RemovePaymentMintV4 { mint });
+
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::state::*;
+
+#[event]
+pub struct RefundV4 {
+    pub order_id: String,
+    pub wallet: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+/// Admin or operator refunds a rejected order
+#[derive(Accounts)]
+#[instruction(order_id: String)]
+pub struct RefundOrder<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Project account (pays the refund)
+    #[account(
+        mut,
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::REFUND) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &hash_seed(&order_id)],
+        bump = order.bump,
+        constraint = order.status == OrderStatus::Pending @ crate::error::ErrorCode::OrderNotPending
+    )]
+    pub order: Box<Account<'info, OrderAccount>>,
+
+    /// Original payer receives the refund
+    /// CHECK: Validated from order account
+    #[account(
+        mut,
+        constraint = payer.key() == order.payer @ crate::error::ErrorCode::InvalidOrderPayer
+    )]
+    pub payer: UncheckedAccount<'info>,
+
+    /// SPL orders only: payment mint config, payment mint, project vault, payer token account and token program
+    #[account(
+        mut,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Option<Box<Account<'info, PaymentMintAccount>>>,
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut)]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub payer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Payer reclaims an order nobody fulfilled or refunded in time
+#[derive(Accounts)]
+#[instruction(order_id: String)]
+pub struct ReclaimOrder<'info> {
+    #[account(
+        mut,
+        constraint = payer.key() == order.payer @ crate::error::ErrorCode::InvalidOrderPayer
+    )]
+    pub payer: Signer<'info>,
+
+    /// Project account (pays the refund)
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_ORDER_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), &hash_seed(&order_id)],
+        bump = order.bump,
+        constraint = order.status == OrderStatus::Pending @ crate::error::ErrorCode::OrderNotPending
+    )]
+    pub order: Box<Account<'info, OrderAccount>>,
+
+    /// SPL orders only: payment mint config, payment mint, project vault, payer token account and token program
+    #[account(
+        mut,
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Option<Box<Account<'info, PaymentMintAccount>>>,
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut)]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub payer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn refund_handler(ctx: Context<RefundOrder>, order_id: String) -> Result<()> {
+    let accounts = &ctx.accounts;
+    if accounts.order.payment_mint.is_some() {
+        pay_back_spl(
+            &accounts.non_transferable_project,
+            &accounts.order,
+            &accounts.payment_mint,
+            &accounts.vault,
+            &accounts.payer_token_account,
+            &accounts.token_program,
+        )?;
+    } else {
+        pay_back(
+            &accounts.non_transferable_project.to_account_info(),
+            &accounts.payer.to_account_info(),
+            accounts.order.amount,
+        )?;
+    }
+
+    let accounts = &mut *ctx.accounts;
+    accounts.order.release_escrow(
+        &mut accounts.non_transferable_project,
+        accounts.payment_mint_config.as_deref_mut().map(|config| &mut **config),
+    )?;
+
+    let order = &mut accounts.order;
+    order.status = OrderStatus::Refunded;
+
+    emit_refund(&order_id, order);
+
+    Ok(())
+}
+
+pub fn reclaim_handler(ctx: Context<ReclaimOrder>, order_id: String) -> Result<()> {
+    let timeout = ctx.accounts.non_transferable_project.reclaim_timeout_slots;
+    let current_slot = Clock::get()?.slot;
+    require!(
+        current_slot >= ctx.accounts.order.slot.saturating_add(timeout),
+        crate::error::ErrorCode::ReclaimTooEarly
+    );
+
+    let accounts = &ctx.accounts;
+    if accounts.order.payment_mint.is_some() {
+        pay_back_spl(
+            &accounts.non_transferable_project,
+            &accounts.order,
+            &accounts.payment_mint,
+            &accounts.vault,
+            &accounts.payer_token_account,
+            &accounts.token_program,
+        )?;
+    } else {
+        pay_back(
+            &accounts.non_transferable_project.to_account_info(),
+            &accounts.payer.to_account_info(),
+            accounts.order.amount,
+        )?;
+    }
+
+    let accounts = &mut *ctx.accounts;
+    accounts.order.release_escrow(
+        &mut accounts.non_transferable_project,
+        accounts.payment_mint_config.as_deref_mut().map(|config| &mut **config),
+    )?;
+
+    let order = &mut accounts.order;
+    order.status = OrderStatus::Cancelled;
+
+    emit_refund(&order_id, order);
+
+    Ok(())
+}
+
+/// Move `amount` lamports from the project account back to the payer,
+/// keeping the project account rent exempt
+fn pay_back(project_info: &AccountInfo, payer_info: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let min_balance = rent.minimum_balance(project_info.data_len());
+    let available = project_info.lamports().saturating_sub(min_balance);
+
+    require!(available >= amount, crate::error::ErrorCode::InsufficientBalance);
+
+    **project_info.try_borrow_mut_lamports()? -= amount;
+    **payer_info.try_borrow_mut_lamports()? += amount;
+
+    Ok(())
+}
+
+/// Move an SPL order's tokens from the project vault back to the payer's token account
+fn pay_back_spl<'info>(
+    project: &Account<'info, ProjectAccount>,
+    order: &OrderAccount,
+    payment_mint: &Option<Box<InterfaceAccount<'info, Mint>>>,
+    vault: &Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    payer_token_account: &Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    token_program: &Option<Interface<'info, TokenInterface>>,
+) -> Result<()> {
+    let (Some(payment_mint), Some(vault), Some(payer_token_account), Some(token_program)) =
+        (payment_mint, vault, payer_token_account, token_program)
+    else {
+        return err!(crate::error::ErrorCode::InvalidAccountData);
+    };
+
+    require!(
+        order.payment_mint == Some(payment_mint.key()),
+        crate::error::ErrorCode::PaymentMintMismatch
+    );
+    require!(
+        vault.mint == payment_mint.key() && vault.owner == project.key(),
+        crate::error::ErrorCode::InvalidAccountData
+    );
+    require!(
+        payer_token_account.mint == payment_mint.key() && payer_token_account.owner == order.payer,
+        crate::error::ErrorCode::InvalidOrderPayer
+    );
+
+    if order.amount == 0 {
+        return Ok(());
+    }
+
+    require!(vault.amount >= order.amount, crate::error::ErrorCode::InsufficientBalance);
+
+    let project_id_bytes = project_id_seed(project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[project.bump],
+    ];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault.to_account_info(),
+                mint: payment_mint.to_account_info(),
+                to: payer_token_account.to_account_info(),
+                authority: project.to_account_info(),
+            },
+            &[project_signer_seeds],
+        ),
+        order.amount,
+        payment_mint.decimals,
+    )
+}
+
+fn emit_refund(order_id: &str, order: &OrderAccount) {
+    msg!("Order refunded: {}", order_id);
+
+    // 输出格式化的事件日志，方便后端解析
+    msg!(
+        "RefundV4:orderId:{};wallet:{};payer:{};amount:{};",
+        order_id,
+        order.wallet,
+        order.payer,
+        order.amount
+    );
+
+    emit!(RefundV4 {
+        order_id: order_id.to_string(),
+        wallet: order.wallet,
+        payer: order.payer,
+        amount: order.amount,
+    });
+}
@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token_interface::TokenInterface;
+use mpl_token_metadata::instructions::UnverifyCollectionV1CpiBuilder;
+
+use crate::state::*;
+
+#[event]
+pub struct RevokeV4 {
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    pub reason: u8,
+}
+
+#[derive(Accounts)]
+pub struct RevokeNonTransferableNft<'info> {
+    /// Admin or operator - receives Mint and wallet index rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::REVOKE) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    /// CHECK: DID holder - validated against the token account owner
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Holder's wallet index PDA, closed in the handler so the wallet can be re-issued
+    /// Empty for DIDs minted before wallet indexes existed
+    #[account(
+        mut,
+        seeds = [WALLET_INDEX_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub wallet_index: UncheckedAccount<'info>,
+
+    /// CHECK: Holder's Token-2022 account - mint and owner checked in handler
+    #[account(mut)]
+    pub user_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: NFT Mint - closed via MintCloseAuthority
+    #[account(mut)]
+    pub non_transferable_nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex metadata PDA of the DID mint (Metaplex projects only), unverified from the collection
+    #[account(mut)]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Collection Mint (Metaplex projects only), the DID is unverified from it
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(non_transferable_project.project_id, &non_transferable_project.key())],
+        bump = non_transferable_project.mint_bump,
+    )]
+    pub collection_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex metadata PDA of the collection mint, its size is decremented by Token Metadata
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, required by Token Metadata
+    #[account(address = instructions_sysvar::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RevokeNonTransferableNft>, reason: u8) -> Result<()> {
+    let project_id_bytes = project_id_seed(ctx.accounts.non_transferable_project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[ctx.accounts.non_transferable_project.bump],
+    ];
+
+    // Token account layout: mint(0..32) + owner(32..64) + amount(64..72)
+    let amount = {
+        let token_account_data = ctx.accounts.user_token_account.try_borrow_data()?;
+        require!(
+            token_account_data.len() >= 72,
+            crate::error::ErrorCode::InvalidAccountData
+        );
+        require!(
+            token_account_data[0..32] == ctx.accounts.non_transferable_nft_mint.key().to_bytes(),
+            crate::error::ErrorCode::InvalidAccountData
+        );
+        require!(
+            token_account_data[32..64] == ctx.accounts.wallet.key().to_bytes(),
+            crate::error::ErrorCode::InvalidAccountData
+        );
+        u64::from_le_bytes(token_account_data[64..72].try_into().unwrap())
+    };
+
+    if ctx.accounts.non_transferable_project.metaplex_metadata {
+        let (
+            Some(nft_metadata),
+            Some(collection_mint),
+            Some(collection_metadata),
+            Some(token_metadata_program),
+            Some(sysvar_instructions),
+        ) = (
+            ctx.accounts.nft_metadata.as_deref(),
+            ctx.accounts.collection_mint.as_deref(),
+            ctx.accounts.collection_metadata.as_deref(),
+            ctx.accounts.token_metadata_program.as_deref(),
+            ctx.accounts.sysvar_instructions.as_deref(),
+        )
+        else {
+            return err!(crate::error::ErrorCode::MetaplexAccountsMissing);
+        };
+
+        // Token Metadata's BurnV1 needs the holder, so the token is burned through the
+        // permanent delegate below. The project PDA, as collection authority, drops the DID
+        // from the verified collection - its metadata and master edition stay behind
+        // and only a holder burn returns their rent
+        msg!("Unverifying DID from the Metaplex collection");
+        UnverifyCollectionV1CpiBuilder::new(token_metadata_program)
+            .authority(&ctx.accounts.non_transferable_project.to_account_info())
+            .metadata(nft_metadata)
+            .collection_mint(collection_mint)
+            .collection_metadata(Some(collection_metadata))
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .sysvar_instructions(sysvar_instructions)
+            .invoke_signed(&[project_signer_seeds])?;
+    }
+
+    if amount > 0 {
+        // Burn through the PermanentDelegate (project PDA), no holder signature needed
+        msg!("Burning {} token(s) via permanent delegate", amount);
+
+        invoke_signed(
+            &spl_token_2022::instruction::burn(
+                &ctx.accounts.token_program.key(),
+                &ctx.accounts.user_token_account.key(),
+                &ctx.accounts.non_transferable_nft_mint.key(),
+                &ctx.accounts.non_transferable_project.key(),
+                &[],
+                amount,
+            )?,
+            &[
+                ctx.accounts.user_token_account.to_account_info(),
+                ctx.accounts.non_transferable_nft_mint.to_account_info(),
+                ctx.accounts.non_transferable_project.to_account_info(),
+            ],
+            &[project_signer_seeds],
+        )?;
+    }
+
+    // Close Mint account (requires MintCloseAuthority extension)
+    msg!("Closing mint account to recover rent");
+    invoke_signed(
+        &spl_token_2022::instruction::close_account(
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.non_transferable_nft_mint.key(),
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.non_transferable_project.key(),
+            &[],
+        )?,
+        &[
+            ctx.accounts.non_transferable_nft_mint.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.non_transferable_project.to_account_info(),
+        ],
+        &[project_signer_seeds],
+    )?;
+
+    crate::utils::close_wallet_index(
+        &ctx.accounts.wallet_index,
+        &ctx.accounts.non_transferable_nft_mint.key(),
+        &ctx.accounts.authority,
+    )?;
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.total_revoked = project.total_revoked.checked_add(1).ok_or(crate::error::ErrorCode::Overflow)?;
+
+    // 输出格式化的事件日志，方便后端解析
+    msg!(
+        "RevokeV4:wallet:{};mint:{};reason:{};",
+        ctx.accounts.wallet.key(),
+        ctx.accounts.non_transferable_nft_mint.key(),
+        reason
+    );
+
+    emit!(RevokeV4 {
+        wallet: ctx.accounts.wallet.key(),
+        mint: ctx.accounts.non_transferable_nft_mint.key(),
+        reason,
+    });
+
+    Ok(())
+}
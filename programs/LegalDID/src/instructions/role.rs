@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct RoleGrantedV4 {
+    pub holder: Pubkey,
+    pub permissions: u32,
+}
+
+#[event]
+pub struct RoleRevokedV4 {
+    pub holder: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ROLE_ACCOUNT_SIZE,
+        seeds = [ROLE_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), holder.as_ref()],
+        bump
+    )]
+    pub role: Account<'info, RoleAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(holder: Pubkey)]
+pub struct RevokeRole<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [ROLE_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), holder.as_ref()],
+        bump = role.bump
+    )]
+    pub role: Account<'info, RoleAccount>,
+}
+
+pub fn grant_handler(ctx: Context<GrantRole>, holder: Pubkey, permissions: u32) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !permissions::ALL == 0,
+        crate::error::ErrorCode::InvalidPermissions
+    );
+
+    let role = &mut ctx.accounts.role;
+    role.project_id = ctx.accounts.non_transferable_project.project_id;
+    role.holder = holder;
+    role.permissions = permissions;
+    role.bump = ctx.bumps.role;
+
+    msg!("RoleGrantedV4:holder:{};permissions:{};", holder, permissions);
+
+    emit!(RoleGrantedV4 {
+        holder,
+        permissions,
+    });
+
+    Ok(())
+}
+
+pub fn revoke_handler(_ctx: Context<RevokeRole>, holder: Pubkey) -> Result<()> {
+    msg!("RoleRevokedV4:holder:{};", holder);
+
+    emit!(RoleRevokedV4 { holder });
+
+    Ok(())
+}
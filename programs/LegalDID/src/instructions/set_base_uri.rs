@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token_interface::TokenInterface;
+use mpl_token_metadata::instructions::UpdateV1CpiBuilder;
+use mpl_token_metadata::types::Data;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Mint as MintState;
+use spl_token_metadata_interface::instruction::update_field;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+
+use crate::state::*;
+
+/// Admin sets base_uri, the project account is resized to fit and the
+/// collection mint's metadata uri is kept in sync (Metaplex metadata too, if the project has it)
+#[derive(Accounts)]
+#[instruction(uri: String)]
+pub struct SetBaseURI<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        realloc = ProjectAccount::space_for(uri.len()),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Pays for any extra project / collection mint space
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Collection Mint (Group), metadata update authority is the project PDA
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(), &project_scope_seed(non_transferable_project.project_id, &non_transferable_project.key())],
+        bump = non_transferable_project.mint_bump,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex metadata PDA of the collection mint (Metaplex projects only), checked by Token Metadata
+    #[account(mut)]
+    pub collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, required by Token Metadata
+    #[account(address = instructions_sysvar::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetBaseURI>, uri: String) -> Result<()> {
+    require!(uri.len() <= MAX_BASE_URI_LEN, crate::error::ErrorCode::BaseUriTooLong);
+    require!(
+        !ctx.accounts.non_transferable_project.metaplex_metadata || uri.len() <= METAPLEX_MAX_BASE_URI_LEN,
+        crate::error::ErrorCode::BaseUriTooLong
+    );
+
+    let mint_info = ctx.accounts.collection_mint.to_account_info();
+
+    // Collection mint size after the uri change
+    let new_len = {
+        let data = mint_info.try_borrow_data()?;
+        let state = StateWithExtensions::<MintState>::unpack(&data)
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+        let metadata = state
+            .get_variable_len_extension::<TokenMetadata>()
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+
+        let old_size = metadata
+            .tlv_size_of()
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+        let mut updated = metadata.clone();
+        updated.uri = uri.clone();
+        let new_size = updated
+            .tlv_size_of()
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+
+        data.len() - old_size + new_size
+    };
+
+    // Top up rent before Token-2022 reallocs the mint for a longer uri
+    let required_rent = Rent::get()?.minimum_balance(new_len);
+    let current_lamports = mint_info.lamports();
+    if required_rent > current_lamports {
+        invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                &ctx.accounts.authority.key(),
+                &mint_info.key(),
+                required_rent - current_lamports,
+            ),
+            &[
+                ctx.accounts.authority.to_account_info(),
+                mint_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    let project = &ctx.accounts.non_transferable_project;
+    let project_id_bytes = project_id_seed(project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[project.bump],
+    ];
+
+    invoke_signed(
+        &update_field(
+            &ctx.accounts.token_program.key(),
+            &mint_info.key(),
+            &project.key(),
+            Field::Uri,
+            uri.clone(),
+        ),
+        &[mint_info.clone(), project.to_account_info()],
+        &[project_signer_seeds],
+    )?;
+
+    if project.metaplex_metadata {
+        let (Some(collection_metadata), Some(token_metadata_program), Some(sysvar_instructions)) = (
+            ctx.accounts.collection_metadata.as_deref(),
+            ctx.accounts.token_metadata_program.as_deref(),
+            ctx.accounts.sysvar_instructions.as_deref(),
+        ) else {
+            return err!(crate::error::ErrorCode::MetaplexAccountsMissing);
+        };
+
+        let project_info = project.to_account_info();
+        UpdateV1CpiBuilder::new(token_metadata_program)
+            .authority(&project_info)
+            .mint(&mint_info)
+            .metadata(collection_metadata)
+            .payer(&ctx.accounts.authority.to_account_info())
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .sysvar_instructions(sysvar_instructions)
+            .data(Data {
+                name: project.name.clone(),
+                symbol: project.symbol.clone(),
+                uri: uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+            })
+            .invoke_signed(&[project_signer_seeds])?;
+    }
+
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.base_uri = uri;
+
+    msg!("SetBaseURI:collectionId:{};uri:{};", project.key(), project.base_uri);
+
+    Ok(())
+}
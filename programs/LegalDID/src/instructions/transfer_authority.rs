@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+#[event]
+pub struct AuthorityProposedV4 {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferredV4 {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelledV4 {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+/// Propose or cancel an authority transfer (current admin only)
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+/// Accept a pending authority transfer (pending admin only)
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.pending_authority.is_some() @ crate::error::ErrorCode::NoPendingAuthority,
+        constraint = non_transferable_project.pending_authority == Some(new_authority.key()) @ crate::error::ErrorCode::InvalidPendingAuthority
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+}
+
+pub fn propose_handler(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let project = &mut ctx.accounts.non_transferable_project;
+    project.pending_authority = Some(new_authority);
+
+    msg!("Authority transfer proposed from {} to {}", project.authority, new_authority);
+
+    emit!(AuthorityProposedV4 {
+        authority: project.authority,
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
+
+pub fn accept_handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let project = &mut ctx.accounts.non_transferable_project;
+    let old_authority = project.authority;
+    project.authority = ctx.accounts.new_authority.key();
+    project.pending_authority = None;
+
+    msg!("Authority transferred from {} to {}", old_authority, project.authority);
+
+    emit!(AuthorityTransferredV4 {
+        old_authority,
+        new_authority: project.authority,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_handler(ctx: Context<ProposeAuthority>) -> Result<()> {
+    let project = &mut ctx.accounts.non_transferable_project;
+    let pending_authority = project
+        .pending_authority
+        .take()
+        .ok_or(crate::error::ErrorCode::NoPendingAuthority)?;
+
+    msg!("Authority transfer to {} cancelled", pending_authority);
+
+    emit!(AuthorityTransferCancelledV4 {
+        authority: project.authority,
+        pending_authority,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token_interface::TokenInterface;
+use mpl_token_metadata::accounts::Metadata;
+use mpl_token_metadata::instructions::UpdateV1CpiBuilder;
+use mpl_token_metadata::types::Data;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Mint as MintState;
+use spl_token_metadata_interface::instruction::update_field;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+
+use crate::state::*;
+
+#[event]
+pub struct UpdateDidV4 {
+    pub mint: Pubkey,
+    pub old_merkle_root: String,
+    pub new_merkle_root: String,
+}
+
+#[derive(Accounts)]
+pub struct UpdateNonTransferableNft<'info> {
+    /// Admin or operator - pays any extra rent for longer metadata
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::UPDATE_METADATA) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused,
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer permission (not needed for admin / legacy operators)
+    pub role: Option<Account<'info, RoleAccount>>,
+
+    /// CHECK: DID Mint - metadata update authority is the project PDA, enforced by Token-2022,
+    /// and the handler rejects the collection mint
+    #[account(
+        mut,
+        owner = token_program.key() @ crate::error::ErrorCode::InvalidMintAccount
+    )]
+    pub non_transferable_nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex metadata PDA of the DID mint (Metaplex projects only), checked by Token Metadata
+    #[account(mut)]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, required by Token Metadata
+    #[account(address = instructions_sysvar::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpdateNonTransferableNft>, merkle_root: String) -> Result<()> {
+    // merkle_root must be exactly 32 bytes of hex, stored in canonical form
+    let merkle_root = crate::utils::canonical_hex32(&merkle_root)?;
+
+    let project = &ctx.accounts.non_transferable_project;
+    let mint_info = ctx.accounts.non_transferable_nft_mint.to_account_info();
+
+    // The collection mint shares the project PDA as update authority, only DIDs may change here
+    let collection_mint = Pubkey::create_program_address(
+        &[
+            NON_TRANSFERABLE_PROJECT_MINT_PREFIX.as_bytes(),
+            &project_scope_seed(project.project_id, &project.key()),
+            &[project.mint_bump],
+        ],
+        ctx.program_id,
+    )
+    .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+    require!(
+        mint_info.key() != collection_mint,
+        crate::error::ErrorCode::InvalidMintAccount
+    );
+
+    let new_uri = format!("{}{}.json", project.base_uri, merkle_root);
+
+    // Read current metadata to find the old root and the post-update account size
+    let (old_merkle_root, has_root_field, new_len) = {
+        let data = mint_info.try_borrow_data()?;
+        let state = StateWithExtensions::<MintState>::unpack(&data)
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+        let metadata = state
+            .get_variable_len_extension::<TokenMetadata>()
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+        require!(
+            metadata.mint == mint_info.key(),
+            crate::error::ErrorCode::InvalidMintAccount
+        );
+
+        let stored_root = metadata
+            .additional_metadata
+            .iter()
+            .find(|(key, _)| key == MERKLE_ROOT_METADATA_KEY)
+            .map(|(_, value)| value.clone());
+        let has_root_field = stored_root.is_some();
+        let old_merkle_root = stored_root.unwrap_or_else(|| {
+            // Fall back to the root embedded in {base_uri}{merkle_root}.json
+            metadata
+                .uri
+                .strip_prefix(project.base_uri.as_str())
+                .and_then(|rest| rest.strip_suffix(".json"))
+                .unwrap_or(metadata.uri.as_str())
+                .to_string()
+        });
+
+        let old_size = metadata
+            .tlv_size_of()
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+        let mut updated = metadata.clone();
+        updated.uri = new_uri.clone();
+        if has_root_field {
+            updated.update(
+                Field::Key(MERKLE_ROOT_METADATA_KEY.to_string()),
+                merkle_root.clone(),
+            );
+        }
+        let new_size = updated
+            .tlv_size_of()
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+
+        (old_merkle_root, has_root_field, data.len() - old_size + new_size)
+    };
+
+    // Top up rent before Token-2022 reallocs the mint for longer values
+    let required_rent = Rent::get()?.minimum_balance(new_len);
+    let current_lamports = mint_info.lamports();
+    if required_rent > current_lamports {
+        invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                &ctx.accounts.authority.key(),
+                &mint_info.key(),
+                required_rent - current_lamports,
+            ),
+            &[
+                ctx.accounts.authority.to_account_info(),
+                mint_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    let project_id_bytes = project_id_seed(project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[project.bump],
+    ];
+
+    invoke_signed(
+        &update_field(
+            &ctx.accounts.token_program.key(),
+            &mint_info.key(),
+            &project.key(),
+            Field::Uri,
+            new_uri.clone(),
+        ),
+        &[mint_info.clone(), project.to_account_info()],
+        &[project_signer_seeds],
+    )?;
+
+    if has_root_field {
+        invoke_signed(
+            &update_field(
+                &ctx.accounts.token_program.key(),
+                &mint_info.key(),
+                &project.key(),
+                Field::Key(MERKLE_ROOT_METADATA_KEY.to_string()),
+                merkle_root.clone(),
+            ),
+            &[mint_info.clone(), project.to_account_info()],
+            &[project_signer_seeds],
+        )?;
+    }
+
+    // Keep the Metaplex copy of the uri in sync for Metaplex projects
+    if project.metaplex_metadata {
+        let (Some(nft_metadata), Some(token_metadata_program), Some(sysvar_instructions)) = (
+            ctx.accounts.nft_metadata.as_deref(),
+            ctx.accounts.token_metadata_program.as_deref(),
+            ctx.accounts.sysvar_instructions.as_deref(),
+        )
+        else {
+            return err!(crate::error::ErrorCode::MetaplexAccountsMissing);
+        };
+
+        let metadata = Metadata::try_from(nft_metadata)
+            .map_err(|_| crate::error::ErrorCode::InvalidAccountData)?;
+        require!(
+            metadata.mint == mint_info.key(),
+            crate::error::ErrorCode::InvalidMintAccount
+        );
+
+        msg!("Updating Metaplex metadata uri");
+        let project_info = project.to_account_info();
+        UpdateV1CpiBuilder::new(token_metadata_program)
+            .authority(&project_info)
+            .mint(&mint_info)
+            .metadata(nft_metadata)
+            .payer(&ctx.accounts.authority.to_account_info())
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .sysvar_instructions(sysvar_instructions)
+            .data(Data {
+                // Token Metadata pads stored strings with NULs
+                name: metadata.name.trim_end_matches('\0').to_string(),
+                symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+                uri: new_uri.clone(),
+                seller_fee_basis_points: metadata.seller_fee_basis_points,
+                creators: metadata.creators,
+            })
+            .invoke_signed(&[project_signer_seeds])?;
+    }
+
+    msg!("DID metadata updated, uri: {}", new_uri);
+
+    // 输出格式化的事件日志，方便后端解析
+    msg!(
+        "UpdateDidV4:mint:{};oldMerkleRoot:{};newMerkleRoot:{};",
+        mint_info.key(),
+        old_merkle_root,
+        merkle_root
+    );
+
+    emit!(UpdateDidV4 {
+        mint: mint_info.key(),
+        old_merkle_root,
+        new_merkle_root: merkle_root,
+    });
+
+    Ok(())
+}
@@ -17,13 +17,14 @@ pub struct Withdraw<'info> {
     /// Project account (holds funds)
     #[account(
         mut,
-        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes()],
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
         bump = non_transferable_project.bump,
         constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
     )]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 
-    /// Destination address (renamed from fee_recipient)
+    /// Destination address (renamed from fee_recipient), paid only when no revenue shares are set
+    /// Revenue share recipients are passed as remaining accounts
     /// CHECK: Validated from project account
     #[account(
         mut,
@@ -34,27 +35,99 @@ pub struct Withdraw<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Withdraw everything above rent and pending escrow, split across the configured revenue shares
 pub fn handler(ctx: Context<Withdraw>) -> Result<()> {
-    let project_account_info = ctx.accounts.non_transferable_project.to_account_info();
-    
-    // Calculate withdrawable amount (total balance - rent exempt minimum)
+    let withdrawable = withdrawable_balance(&ctx.accounts.non_transferable_project)?;
+
+    require!(withdrawable > 0, crate::error::ErrorCode::InsufficientBalance);
+
+    distribute(&ctx, withdrawable)
+}
+
+/// Withdraw a fixed amount, split the same way as a full withdraw
+pub fn amount_handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+    let withdrawable = withdrawable_balance(&ctx.accounts.non_transferable_project)?;
+
+    require!(
+        amount > 0 && amount <= withdrawable,
+        crate::error::ErrorCode::InsufficientBalance
+    );
+
+    distribute(&ctx, amount)
+}
+
+/// Total balance minus the rent exempt minimum and the lamports escrowed for pending orders
+fn withdrawable_balance(project: &Account<ProjectAccount>) -> Result<u64> {
+    let project_account_info = project.to_account_info();
     let rent = Rent::get()?;
     let min_balance = rent.minimum_balance(project_account_info.data_len());
-    let current_balance = project_account_info.lamports();
-    
-    let withdrawable = current_balance.saturating_sub(min_balance);
-    
-    require!(withdrawable > 0, crate::error::ErrorCode::InsufficientBalance);
+    Ok(project_account_info
+        .lamports()
+        .saturating_sub(min_balance)
+        .saturating_sub(project.pending_escrow))
+}
+
+/// Pay `amount` out of the project account
+/// With no revenue shares everything goes to destination, otherwise recipients are
+/// passed as remaining accounts in share order and the last one takes the rounding dust
+fn distribute(ctx: &Context<Withdraw>, amount: u64) -> Result<()> {
+    let project_account_info = ctx.accounts.non_transferable_project.to_account_info();
+    let shares = &ctx.accounts.non_transferable_project.revenue_shares;
+
+    if shares.is_empty() {
+        return pay_out(&project_account_info, &ctx.accounts.destination, amount);
+    }
+
+    require!(
+        ctx.remaining_accounts.len() == shares.len(),
+        crate::error::ErrorCode::RevenueRecipientMismatch
+    );
+
+    for ((share, recipient), portion) in shares
+        .iter()
+        .zip(ctx.remaining_accounts.iter())
+        .zip(split_by_shares(amount, shares))
+    {
+        require!(
+            recipient.key() == share.recipient,
+            crate::error::ErrorCode::RevenueRecipientMismatch
+        );
+
+        if portion > 0 {
+            pay_out(&project_account_info, recipient, portion)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Split `amount` by revenue share bps, the last share takes the rounding dust
+pub(crate) fn split_by_shares(amount: u64, shares: &[RevenueShare]) -> Vec<u64> {
+    let mut remaining = amount;
+    shares
+        .iter()
+        .enumerate()
+        .map(|(i, share)| {
+            let portion = if i == shares.len() - 1 {
+                remaining
+            } else {
+                (amount as u128 * share.bps as u128 / BPS_DENOMINATOR as u128) as u64
+            };
+            remaining -= portion;
+            portion
+        })
+        .collect()
+}
 
-    // Transfer from project account to destination
-    **project_account_info.try_borrow_mut_lamports()? -= withdrawable;
-    **ctx.accounts.destination.try_borrow_mut_lamports()? += withdrawable;
+fn pay_out(project_account_info: &AccountInfo, recipient: &AccountInfo, amount: u64) -> Result<()> {
+    **project_account_info.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
 
-    msg!("Withdrawn {} lamports to {}", withdrawable, ctx.accounts.destination.key());
+    msg!("Withdrawn {} lamports to {}", amount, recipient.key());
 
     emit!(WithdrawV4 {
-        recipient: ctx.accounts.destination.key(),
-        amount: withdrawable,
+        recipient: recipient.key(),
+        amount,
     });
 
     Ok(())
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::instructions::withdraw::split_by_shares;
+use crate::state::*;
+
+#[event]
+pub struct WithdrawSplV4 {
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSpl<'info> {
+    /// Admin only
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(), &project_id_seed(non_transferable_project.project_id)],
+        bump = non_transferable_project.bump,
+        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
+    )]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    #[account(mint::token_program = token_program)]
+    pub payment_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Payment mint config, counts the vault's pending escrow
+    #[account(
+        seeds = [PAYMENT_MINT_PREFIX.as_bytes(), non_transferable_project.key().as_ref(), payment_mint.key().as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Box<Account<'info, PaymentMintAccount>>,
+
+    /// Project vault (ATA owned by the project PDA)
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = non_transferable_project,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Destination's token account for the payment mint, paid only when no revenue shares are set
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::token_program = token_program,
+        constraint = destination_token_account.owner == non_transferable_project.destination @ crate::error::ErrorCode::InvalidFeeRecipient
+    )]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Withdraw the vault balance minus the tokens escrowed for pending orders
+/// With no revenue shares everything goes to destination's token account, otherwise the
+/// recipients' token accounts for the payment mint are passed as remaining accounts in share order
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, WithdrawSpl<'info>>) -> Result<()> {
+    let amount = ctx
+        .accounts
+        .vault
+        .amount
+        .saturating_sub(ctx.accounts.payment_mint_config.pending_escrow);
+
+    require!(amount > 0, crate::error::ErrorCode::InsufficientBalance);
+
+    let shares = &ctx.accounts.non_transferable_project.revenue_shares;
+    if shares.is_empty() {
+        return pay_out(&ctx, &ctx.accounts.destination_token_account.to_account_info(), amount);
+    }
+
+    require!(
+        ctx.remaining_accounts.len() == shares.len(),
+        crate::error::ErrorCode::RevenueRecipientMismatch
+    );
+
+    for ((share, recipient), portion) in shares
+        .iter()
+        .zip(ctx.remaining_accounts.iter())
+        .zip(split_by_shares(amount, shares))
+    {
+        let recipient_token_account = InterfaceAccount::<TokenAccount>::try_from(recipient)?;
+        require!(
+            recipient_token_account.owner == share.recipient
+                && recipient_token_account.mint == ctx.accounts.payment_mint.key(),
+            crate::error::ErrorCode::RevenueRecipientMismatch
+        );
+
+        if portion > 0 {
+            pay_out(&ctx, recipient, portion)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn pay_out<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, WithdrawSpl<'info>>,
+    recipient: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let project_id_bytes = project_id_seed(ctx.accounts.non_transferable_project.project_id);
+    let project_signer_seeds: &[&[u8]] = &[
+        NON_TRANSFERABLE_PROJECT_PREFIX.as_bytes(),
+        &project_id_bytes,
+        &[ctx.accounts.non_transferable_project.bump],
+    ];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.payment_mint.to_account_info(),
+                to: recipient.clone(),
+                authority: ctx.accounts.non_transferable_project.to_account_info(),
+            },
+            &[project_signer_seeds],
+        ),
+        amount,
+        ctx.accounts.payment_mint.decimals,
+    )?;
+
+    msg!(
+        "Withdrawn {} of mint {} to {}",
+        amount,
+        ctx.accounts.payment_mint.key(),
+        recipient.key()
+    );
+
+    emit!(WithdrawSplV4 {
+        recipient: recipient.key(),
+        mint: ctx.accounts.payment_mint.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -11,114 +11,311 @@ use state::*;
 // Current devnet program ID - will be replaced with new mainnet ID during deployment
 declare_id!("BE6yuzEjzapwBEQ9RoAFzG72XZzb17JuWWqhecFHdEQa");
 
-#[program]
-pub mod legaldid {
+// The IDL instruction handlers generated by #[program] still call the deprecated AccountInfo::realloc.
+// They're emitted next to the program module rather than inside it, so the allow sits on this wrapper
+#[allow(deprecated)]
+mod program_module {
     use super::*;
 
-    /// Initialize project
-    pub fn initialize(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
-        initialize::handler(ctx, args)
-    }
+    #[program]
+    pub mod legaldid {
+        use super::*;
 
-    /// Set mint price
-    pub fn set_mint_price(ctx: Context<SetMintPriceContext>, mint_price: u64) -> Result<()> {
-        let project = &mut ctx.accounts.non_transferable_project;
-        project.mint_price = mint_price;
-        msg!(
-            "SetMintPrice:collectionId:{}, price:{}",
-            project.key(),
-            project.mint_price
-        );
-        Ok(())
-    }
+        /// Initialize project (ids other than 0 need the legacy project or upgrade authority)
+        pub fn initialize(ctx: Context<Initialize>, args: InitializeArgs) -> Result<()> {
+            initialize::handler(ctx, args)
+        }
 
-    /// Set Base URI
-    pub fn set_base_uri(ctx: Context<SetBaseURI>, uri: String) -> Result<()> {
-        let state = &mut ctx.accounts.non_transferable_project;
-        state.base_uri = uri;
-        Ok(())
-    }
+        /// Upgrade the project account to the current layout in place (admin only)
+        pub fn migrate_project(ctx: Context<MigrateProject>, project_id: u64) -> Result<()> {
+            migrate::handler(ctx, project_id)
+        }
 
-    /// Set fee recipient (admin only)
-    /// @deprecated Use set_destination instead
-    pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>, fee_recipient: Pubkey) -> Result<()> {
-        let state = &mut ctx.accounts.non_transferable_project;
-        state.destination = fee_recipient;
-        Ok(())
-    }
+        /// Set mint price
+        pub fn set_mint_price(ctx: Context<SetMintPriceContext>, mint_price: u64) -> Result<()> {
+            let project = &mut ctx.accounts.non_transferable_project;
+            project.mint_price = mint_price;
+            msg!(
+                "SetMintPrice:collectionId:{}, price:{}",
+                project.key(),
+                project.mint_price
+            );
+            Ok(())
+        }
 
-    /// Set fund destination address (admin only)
-    pub fn set_fund_destination(ctx: Context<SetFundDestination>, destination: Pubkey) -> Result<()> {
-        let state = &mut ctx.accounts.non_transferable_project;
-        state.destination = destination;
-        Ok(())
-    }
+        /// Accept an SPL payment mint or update its price (admin or SET_PRICE)
+        pub fn set_payment_mint(ctx: Context<SetPaymentMint>, price: u64) -> Result<()> {
+            payment_mint::set_handler(ctx, price)
+        }
 
-    /// Add operator (admin only)
-    pub fn add_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
-        let project = &mut ctx.accounts.non_transferable_project;
-        require!(
-            project.operators.len() < MAX_OPERATORS,
-            error::ErrorCode::MaxOperatorsReached
-        );
-        require!(
-            !project.operators.contains(&operator),
-            error::ErrorCode::OperatorAlreadyExists
-        );
-        project.operators.push(operator);
-        msg!("Operator added: {}", operator);
-        Ok(())
-    }
+        /// Stop accepting an SPL payment mint (admin only)
+        pub fn remove_payment_mint(ctx: Context<RemovePaymentMint>) -> Result<()> {
+            payment_mint::remove_handler(ctx)
+        }
 
-    /// Remove operator (admin only)
-    pub fn remove_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
-        let project = &mut ctx.accounts.non_transferable_project;
-        let index = project
-            .operators
-            .iter()
-            .position(|x| *x == operator)
-            .ok_or(error::ErrorCode::OperatorNotFound)?;
-        project.operators.remove(index);
-        msg!("Operator removed: {}", operator);
-        Ok(())
-    }
+        /// Set Base URI
+        pub fn set_base_uri(ctx: Context<SetBaseURI>, uri: String) -> Result<()> {
+            set_base_uri::handler(ctx, uri)
+        }
 
-    /// User pays to request DID mint (emits event, backend reviews and mints)
-    pub fn authorize_mint(
-        ctx: Context<AuthorizeMint>,
-        order_id: String,
-    ) -> Result<()> {
-        authorize_mint::handler(ctx, order_id)
-    }
+        /// Set fee recipient (admin only)
+        /// @deprecated Use set_destination instead
+        pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>, fee_recipient: Pubkey) -> Result<()> {
+            let state = &mut ctx.accounts.non_transferable_project;
+            state.destination = fee_recipient;
+            Ok(())
+        }
 
-    pub fn airdrop(
-        ctx: Context<MintNonTransferableNft>,
-        order_id: String,
-        wallet: Pubkey,
-        merkle_root: String,
-    ) -> Result<()> {
-        airdrop::handler(ctx, order_id, wallet, merkle_root)
-    }
+        /// Set fund destination address (admin only)
+        pub fn set_fund_destination(ctx: Context<SetFundDestination>, destination: Pubkey) -> Result<()> {
+            let state = &mut ctx.accounts.non_transferable_project;
+            state.destination = destination;
+            Ok(())
+        }
 
-    /// User voluntarily burns their own DID
-    /// Uses mint address directly - no order_id needed since mint is already known
-    pub fn burn(ctx: Context<BurnNonTransferableNft>) -> Result<()> {
-        burn::handler(ctx)
-    }
+        /// Set the withdraw revenue split (admin only), empty sends everything to destination
+        pub fn set_revenue_shares(ctx: Context<SetRevenueShares>, shares: Vec<RevenueShare>) -> Result<()> {
+            require!(
+                shares.len() <= MAX_REVENUE_RECIPIENTS,
+                error::ErrorCode::InvalidRevenueShares
+            );
+            if !shares.is_empty() {
+                let total: u32 = shares.iter().map(|share| share.bps as u32).sum();
+                require!(
+                    total == BPS_DENOMINATOR as u32 && shares.iter().all(|share| share.bps > 0),
+                    error::ErrorCode::InvalidRevenueShares
+                );
+                for (i, share) in shares.iter().enumerate() {
+                    require!(
+                        !shares[..i].iter().any(|other| other.recipient == share.recipient),
+                        error::ErrorCode::InvalidRevenueShares
+                    );
+                }
+            }
 
-    /// Withdraw accumulated fees to fee_recipient (admin only)
-    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
-        withdraw::handler(ctx)
-    }
+            let state = &mut ctx.accounts.non_transferable_project;
+            state.revenue_shares = shares;
+            msg!("SetRevenueShares:recipients:{}", state.revenue_shares.len());
+            Ok(())
+        }
 
-    /// Transfer authority to new admin (current admin only)
-    pub fn transfer_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
-        let project = &mut ctx.accounts.non_transferable_project;
-        let old_authority = project.authority;
-        project.authority = new_authority;
-        
-        msg!("Authority transferred from {} to {}", old_authority, new_authority);
-        Ok(())
-    }
+        /// Update the collection's max_size to cap supply (admin only)
+        pub fn set_collection_max_size(ctx: Context<SetCollectionMaxSize>, max_size: u64) -> Result<()> {
+            collection::set_max_size_handler(ctx, max_size)
+        }
+
+        /// Set or clear the guardian key that can pause the project (admin only)
+        pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+            pause::set_guardian_handler(ctx, guardian)
+        }
+
+        /// Set pause flags (admin, or guardian which can only add flags)
+        pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+            pause::set_paused_handler(ctx, paused)
+        }
+
+        /// Set how many slots a payer must wait before reclaiming an unfulfilled order (admin only)
+        pub fn set_reclaim_timeout(ctx: Context<SetReclaimTimeout>, slots: u64) -> Result<()> {
+            require!(slots > 0, error::ErrorCode::InvalidDataProvided);
+            let state = &mut ctx.accounts.non_transferable_project;
+            state.reclaim_timeout_slots = slots;
+            msg!("SetReclaimTimeout:slots:{}", slots);
+            Ok(())
+        }
+
+        /// Add operator (admin only)
+        pub fn add_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+            let project = &mut ctx.accounts.non_transferable_project;
+            require!(
+                project.operators.len() < MAX_OPERATORS,
+                error::ErrorCode::MaxOperatorsReached
+            );
+            require!(
+                !project.operators.contains(&operator),
+                error::ErrorCode::OperatorAlreadyExists
+            );
+            project.operators.push(operator);
+            msg!("Operator added: {}", operator);
+            Ok(())
+        }
+
+        /// Remove operator (admin only)
+        pub fn remove_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+            let project = &mut ctx.accounts.non_transferable_project;
+            let index = project
+                .operators
+                .iter()
+                .position(|x| *x == operator)
+                .ok_or(error::ErrorCode::OperatorNotFound)?;
+            project.operators.remove(index);
+            msg!("Operator removed: {}", operator);
+            Ok(())
+        }
+
+        /// Grant or update a role's permission bitflags (admin only)
+        pub fn grant_role(ctx: Context<GrantRole>, holder: Pubkey, permissions: u32) -> Result<()> {
+            role::grant_handler(ctx, holder, permissions)
+        }
+
+        /// Remove a role holder (admin only)
+        pub fn revoke_role(ctx: Context<RevokeRole>, holder: Pubkey) -> Result<()> {
+            role::revoke_handler(ctx, holder)
+        }
+
+        /// Block a wallet from authorize_mint / airdrop (MANAGE_BLACKLIST)
+        pub fn block_wallet(ctx: Context<BlockWallet>, wallet: Pubkey) -> Result<()> {
+            blacklist::block_wallet_handler(ctx, wallet)
+        }
+
+        /// Unblock a wallet (MANAGE_BLACKLIST)
+        pub fn unblock_wallet(ctx: Context<UnblockWallet>, wallet: Pubkey) -> Result<()> {
+            blacklist::unblock_wallet_handler(ctx, wallet)
+        }
+
+        /// Block an LDID (identified by its merkle root) from airdrop (MANAGE_BLACKLIST)
+        pub fn block_ldid(ctx: Context<BlockLdid>, ldid: String) -> Result<()> {
+            blacklist::block_ldid_handler(ctx, ldid)
+        }
 
+        /// Unblock an LDID (MANAGE_BLACKLIST)
+        pub fn unblock_ldid(ctx: Context<UnblockLdid>, ldid: String) -> Result<()> {
+            blacklist::unblock_ldid_handler(ctx, ldid)
+        }
+
+        /// User pays to request DID mint (records order, backend reviews and mints)
+        pub fn authorize_mint(
+            ctx: Context<AuthorizeMint>,
+            order_id: String,
+            wallet: Pubkey,
+        ) -> Result<()> {
+            authorize_mint::handler(ctx, order_id, wallet)
+        }
+
+        /// User pays in an accepted SPL token (e.g. USDC) to request DID mint
+        pub fn authorize_mint_spl(
+            ctx: Context<AuthorizeMintSpl>,
+            order_id: String,
+            wallet: Pubkey,
+        ) -> Result<()> {
+            authorize_mint_spl::handler(ctx, order_id, wallet)
+        }
+
+        pub fn airdrop(
+            ctx: Context<MintNonTransferableNft>,
+            order_id: String,
+            wallet: Pubkey,
+            merkle_root: String,
+        ) -> Result<()> {
+            airdrop::handler(ctx, order_id, wallet, merkle_root)
+        }
+
+        /// Mint several DIDs in one instruction, stops early when compute runs low
+        pub fn airdrop_batch<'info>(
+            ctx: Context<'_, '_, 'info, 'info, AirdropBatch<'info>>,
+            entries: Vec<AirdropEntry>,
+        ) -> Result<()> {
+            airdrop_batch::handler(ctx, entries)
+        }
+
+        /// Create a Bubblegum tree owned by the project and switch to compressed DIDs (admin only, once)
+        pub fn initialize_compressed_tree(
+            ctx: Context<InitializeCompressedTree>,
+            max_depth: u32,
+            max_buffer_size: u32,
+        ) -> Result<()> {
+            compressed::initialize_tree_handler(ctx, max_depth, max_buffer_size)
+        }
+
+        /// Mint a soulbound compressed DID (compressed projects only)
+        pub fn airdrop_compressed(
+            ctx: Context<AirdropCompressed>,
+            order_id: String,
+            wallet: Pubkey,
+            merkle_root: String,
+        ) -> Result<()> {
+            compressed::airdrop_handler(ctx, order_id, wallet, merkle_root)
+        }
+
+        /// Holder burns their compressed DID, proof nodes as remaining accounts
+        pub fn burn_compressed<'info>(
+            ctx: Context<'_, '_, 'info, 'info, BurnCompressed<'info>>,
+            root: [u8; 32],
+            data_hash: [u8; 32],
+            creator_hash: [u8; 32],
+            nonce: u64,
+            index: u32,
+        ) -> Result<()> {
+            compressed::burn_handler(ctx, root, data_hash, creator_hash, nonce, index)
+        }
+
+        /// User mints their own DID with an operator-signed voucher, paying the rent
+        /// Must follow an Ed25519 precompile instruction verifying the voucher
+        pub fn claim_did(
+            ctx: Context<ClaimDid>,
+            order_id: String,
+            merkle_root: String,
+            expiry: i64,
+        ) -> Result<()> {
+            claim_did::handler(ctx, order_id, merkle_root, expiry)
+        }
+
+        /// User voluntarily burns their own DID
+        /// Uses mint address directly - no order_id needed since mint is already known
+        pub fn burn(ctx: Context<BurnNonTransferableNft>) -> Result<()> {
+            burn::handler(ctx)
+        }
+
+        /// Refund a pending order to its payer (REFUND)
+        pub fn refund_order(ctx: Context<RefundOrder>, order_id: String) -> Result<()> {
+            refund::refund_handler(ctx, order_id)
+        }
+
+        /// Payer reclaims a pending order after the reclaim timeout has passed
+        pub fn reclaim_order(ctx: Context<ReclaimOrder>, order_id: String) -> Result<()> {
+            refund::reclaim_handler(ctx, order_id)
+        }
+
+        /// Rewrite a DID's merkle root and metadata URI after re-verification (UPDATE_METADATA)
+        pub fn update_did(ctx: Context<UpdateNonTransferableNft>, merkle_root: String) -> Result<()> {
+            update_did::handler(ctx, merkle_root)
+        }
+
+        /// Force-burn a DID through the PermanentDelegate for compliance revocation (REVOKE)
+        pub fn revoke_did(ctx: Context<RevokeNonTransferableNft>, reason: u8) -> Result<()> {
+            revoke::handler(ctx, reason)
+        }
+
+        /// Withdraw accumulated fees to destination, or split across revenue shares (admin only)
+        pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+            withdraw::handler(ctx)
+        }
+
+        /// Withdraw part of the balance, split like withdraw (admin only)
+        pub fn withdraw_amount(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+            withdraw::amount_handler(ctx, amount)
+        }
+
+        /// Withdraw an SPL vault balance, split like withdraw (admin only)
+        pub fn withdraw_spl<'info>(ctx: Context<'_, '_, 'info, 'info, WithdrawSpl<'info>>) -> Result<()> {
+            withdraw_spl::handler(ctx)
+        }
+
+        /// Propose a new admin (current admin only), takes effect after accept_authority
+        pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+            transfer_authority::propose_handler(ctx, new_authority)
+        }
+
+        /// Pending admin accepts the authority transfer
+        pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+            transfer_authority::accept_handler(ctx)
+        }
+
+        /// Cancel a pending authority transfer (current admin only)
+        pub fn cancel_authority_transfer(ctx: Context<ProposeAuthority>) -> Result<()> {
+            transfer_authority::cancel_handler(ctx)
+        }
+
+    }
 }
+
+pub use program_module::*;
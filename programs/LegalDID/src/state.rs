@@ -7,21 +7,107 @@ use sha2::{Digest, Sha256};
 pub const NON_TRANSFERABLE_PROJECT_PREFIX: &str = "nt-proj-v5";
 pub const NON_TRANSFERABLE_PROJECT_MINT_PREFIX: &str = "nt-project-mint-v5";
 pub const NON_TRANSFERABLE_NFT_MINT_PREFIX: &str = "nt-nft-mint-v5";
+pub const NON_TRANSFERABLE_ORDER_PREFIX: &str = "nt-order-v5";
+pub const BLOCKED_WALLET_PREFIX: &str = "nt-blk-wallet-v5";
+pub const BLOCKED_LDID_PREFIX: &str = "nt-blk-ldid-v5";
+pub const WALLET_INDEX_PREFIX: &str = "nt-wallet-idx-v5";
+pub const ROLE_PREFIX: &str = "nt-role-v5";
+pub const PAYMENT_MINT_PREFIX: &str = "nt-pay-mint-v5";
+
+// Role permission bitflags
+pub mod permissions {
+    pub const MINT: u32 = 1 << 0;
+    pub const SET_PRICE: u32 = 1 << 1;
+    pub const REVOKE: u32 = 1 << 2;
+    pub const REFUND: u32 = 1 << 3;
+    pub const MANAGE_BLACKLIST: u32 = 1 << 4;
+    pub const UPDATE_METADATA: u32 = 1 << 5;
+
+    pub const ALL: u32 = MINT | SET_PRICE | REVOKE | REFUND | MANAGE_BLACKLIST | UPDATE_METADATA;
+
+    // Held by every operator without a role, pricing needs a SET_PRICE role
+    pub const OPERATOR_DEFAULT: u32 = MINT | REVOKE | REFUND | MANAGE_BLACKLIST | UPDATE_METADATA;
+}
+
+// Pause bitflags, user-facing and operator-facing paths pause independently
+pub mod pause_flags {
+    pub const USER: u8 = 1 << 0;
+    pub const OPERATOR: u8 = 1 << 1;
+
+    pub const ALL: u8 = USER | OPERATOR;
+}
+
+// Token-2022 additional metadata keys
+pub const MERKLE_ROOT_METADATA_KEY: &str = "merkle_root";
+pub const ORDER_ID_METADATA_KEY: &str = "order_id";
+pub const ISSUED_AT_METADATA_KEY: &str = "issued_at";
+
+// String limits, reserved space includes the 4 byte length prefix
+pub const NAME_RESERVED_SPACE: usize = 100;
+pub const SYMBOL_RESERVED_SPACE: usize = 100;
+pub const BASE_URI_RESERVED_SPACE: usize = 200;
+pub const MAX_NAME_LEN: usize = NAME_RESERVED_SPACE - 4;
+pub const MAX_SYMBOL_LEN: usize = SYMBOL_RESERVED_SPACE - 4;
+// base_uri may outgrow its reserved space, set_base_uri reallocs the project account
+pub const MAX_BASE_URI_LEN: usize = 512;
+
+// Project created before multi-project support, its PDAs have no project component
+pub const LEGACY_PROJECT_ID: u64 = 0;
 
 // Maximum number of operators
 pub const MAX_OPERATORS: usize = 5;
 
-// Project account size (optimized)
-pub const NON_TRANSFERABLE_PROJECT_SIZE: usize = 8 +   // discriminator
+// Maximum number of revenue split recipients
+pub const MAX_REVENUE_RECIPIENTS: usize = 5;
+
+// Revenue shares are expressed in basis points and must sum to this
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+// airdrop_batch: remaining accounts per DID, and the compute left below which it stops minting
+pub const AIRDROP_BATCH_ACCOUNTS_PER_ENTRY: usize = 7;
+pub const AIRDROP_BATCH_MIN_COMPUTE_UNITS: u64 = 120_000;
+
+// Default slots before a payer can reclaim an unfulfilled order (~7 days at 400ms/slot)
+pub const DEFAULT_RECLAIM_TIMEOUT_SLOTS: u64 = 1_512_000;
+
+// Current ProjectAccount layout version, bump when appending fields and teach migrate_project the old layout
+pub const PROJECT_ACCOUNT_VERSION: u8 = 4;
+
+// Metaplex metadata limits (compressed DIDs and Metaplex compatibility mode)
+pub const METAPLEX_MAX_NAME_LEN: usize = 32;
+pub const METAPLEX_MAX_SYMBOL_LEN: usize = 10;
+// Metaplex uri limit is 200, DID uris append "<merkle_root hex>.json" (69 bytes) to base_uri
+pub const METAPLEX_MAX_BASE_URI_LEN: usize = 200 - 69;
+
+// Project account size at layout version 1
+pub const NON_TRANSFERABLE_PROJECT_SIZE_V1: usize = 8 +   // discriminator
     32 +    // authority (admin)
     8 +     // mint_price
     32 +    // destination (renamed from fee_recipient)
     1 +     // bump
     1 +     // mint_bump
-    100 +   // name
-    100 +   // symbol
-    200 +   // base_uri
-    4 + (32 * MAX_OPERATORS); // operators vec (4 bytes len + 5 * 32 bytes)
+    NAME_RESERVED_SPACE +     // name
+    SYMBOL_RESERVED_SPACE +   // symbol
+    BASE_URI_RESERVED_SPACE + // base_uri (grows via realloc past this)
+    4 + (32 * MAX_OPERATORS) + // operators vec (4 bytes len + 5 * 32 bytes)
+    8 +     // reclaim_timeout_slots
+    1 + 32 + // pending_authority (Option<Pubkey>)
+    8 +     // project_id
+    4 + ((32 + 2) * MAX_REVENUE_RECIPIENTS) + // revenue_shares vec (4 bytes len + 5 * (recipient + bps))
+    1 + 32 + // guardian (Option<Pubkey>)
+    1 +     // paused
+    8 +     // total_minted
+    8 +     // total_burned
+    8 +     // total_revoked
+    8 +     // total_fees_collected
+    1;      // version
+
+// Project account size (optimized)
+pub const NON_TRANSFERABLE_PROJECT_SIZE: usize = NON_TRANSFERABLE_PROJECT_SIZE_V1 +
+    1 +     // issuance_mode
+    1 + 32 + // merkle_tree (Option<Pubkey>)
+    1 +     // metaplex_metadata
+    8;      // pending_escrow
 
 // Token-2022 Mint space (with NonTransferable + PermanentDelegate extensions)
 // Base Mint: 82 bytes
@@ -31,6 +117,41 @@ pub const NON_TRANSFERABLE_PROJECT_SIZE: usize = 8 +   // discriminator
 // Account type: 1 byte
 pub const TOKEN2022_MINT_SIZE: usize = 234;
 
+// Order account size
+pub const ORDER_ACCOUNT_SIZE: usize = 8 + // discriminator
+    32 +    // payer
+    32 +    // wallet (beneficiary)
+    8 +     // amount
+    8 +     // slot
+    1 +     // status
+    1 +     // bump
+    1 + 32; // payment_mint (Option<Pubkey>)
+
+// Payment mint config size
+pub const PAYMENT_MINT_SIZE: usize = 8 + // discriminator
+    32 +    // mint
+    8 +     // price
+    1 +     // bump
+    8;      // pending_escrow
+
+// Wallet index size
+pub const WALLET_INDEX_SIZE: usize = 8 + // discriminator
+    32 +    // mint
+    1;      // bump
+
+// Role account size
+pub const ROLE_ACCOUNT_SIZE: usize = 8 + // discriminator
+    8 +     // project_id
+    32 +    // holder
+    4 +     // permissions
+    1;      // bump
+
+// Blacklist entry size
+pub const BLACKLIST_ENTRY_SIZE: usize = 8 + // discriminator
+    32 +    // added_by
+    8 +     // added_at
+    1;      // bump
+
 
 
 
@@ -52,6 +173,121 @@ pub struct ProjectAccount {
     pub symbol: String,
     pub base_uri: String,
     pub operators: Vec<Pubkey>, // Operator list (SECONDARY_ADMIN_ROLE)
+    pub reclaim_timeout_slots: u64, // Slots before payer can reclaim a pending order
+    pub pending_authority: Option<Pubkey>, // Proposed admin, must accept to take over
+    pub project_id: u64,        // Project PDA seed, one program hosts many collections
+    pub revenue_shares: Vec<RevenueShare>, // Withdraw split, empty = everything to destination
+    pub guardian: Option<Pubkey>, // Emergency key that can pause (but not unpause)
+    pub paused: u8,             // Bitflags from `pause_flags`
+    pub total_minted: u64,      // DIDs minted by airdrop / claim_did
+    pub total_burned: u64,      // DIDs burned by their holder
+    pub total_revoked: u64,     // DIDs revoked by an operator
+    pub total_fees_collected: u64, // Lamports paid via authorize_mint (SPL payments not included)
+    pub version: u8,            // Layout version, see PROJECT_ACCOUNT_VERSION
+    pub issuance_mode: IssuanceMode, // Token-2022 mints or compressed NFTs
+    pub merkle_tree: Option<Pubkey>, // Bubblegum tree for compressed mode
+    pub metaplex_metadata: bool, // Also issue Metaplex metadata + verified collection
+    pub pending_escrow: u64,    // Lamports paid for orders still Pending, not withdrawable
+}
+
+/// How a project issues DIDs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum IssuanceMode {
+    #[default]
+    Token2022,
+    Compressed,
+}
+
+/// Original single-project ProjectAccount layout, before `version` was added (read by migrate_project only)
+/// Only the legacy project at [NON_TRANSFERABLE_PROJECT_PREFIX] can have it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ProjectAccountV0 {
+    pub authority: Pubkey,
+    pub mint_price: u64,
+    pub destination: Pubkey,
+    pub bump: u8,
+    pub mint_bump: u8,
+    pub name: String,
+    pub symbol: String,
+    pub base_uri: String,
+    pub operators: Vec<Pubkey>,
+}
+
+impl From<ProjectAccountV0> for ProjectAccount {
+    fn from(old: ProjectAccountV0) -> Self {
+        ProjectAccount {
+            authority: old.authority,
+            mint_price: old.mint_price,
+            destination: old.destination,
+            bump: old.bump,
+            mint_bump: old.mint_bump,
+            name: old.name,
+            symbol: old.symbol,
+            base_uri: old.base_uri,
+            operators: old.operators,
+            reclaim_timeout_slots: DEFAULT_RECLAIM_TIMEOUT_SLOTS,
+            project_id: LEGACY_PROJECT_ID,
+            version: 0,
+            ..Default::default()
+        }
+    }
+}
+
+/// ProjectAccount layout version 1 (read by migrate_project only)
+/// Every later layout starts with these fields, so `version` can be read from any account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ProjectAccountV1 {
+    pub authority: Pubkey,
+    pub mint_price: u64,
+    pub destination: Pubkey,
+    pub bump: u8,
+    pub mint_bump: u8,
+    pub name: String,
+    pub symbol: String,
+    pub base_uri: String,
+    pub operators: Vec<Pubkey>,
+    pub reclaim_timeout_slots: u64,
+    pub pending_authority: Option<Pubkey>,
+    pub project_id: u64,
+    pub revenue_shares: Vec<RevenueShare>,
+    pub guardian: Option<Pubkey>,
+    pub paused: u8,
+    pub total_minted: u64,
+    pub total_burned: u64,
+    pub total_revoked: u64,
+    pub total_fees_collected: u64,
+    pub version: u8,
+}
+
+impl From<ProjectAccountV1> for ProjectAccount {
+    fn from(old: ProjectAccountV1) -> Self {
+        ProjectAccount {
+            authority: old.authority,
+            mint_price: old.mint_price,
+            destination: old.destination,
+            bump: old.bump,
+            mint_bump: old.mint_bump,
+            name: old.name,
+            symbol: old.symbol,
+            base_uri: old.base_uri,
+            operators: old.operators,
+            reclaim_timeout_slots: old.reclaim_timeout_slots,
+            pending_authority: old.pending_authority,
+            project_id: old.project_id,
+            revenue_shares: old.revenue_shares,
+            guardian: old.guardian,
+            paused: old.paused,
+            total_minted: old.total_minted,
+            total_burned: old.total_burned,
+            total_revoked: old.total_revoked,
+            total_fees_collected: old.total_fees_collected,
+            version: old.version,
+            issuance_mode: IssuanceMode::Token2022,
+            merkle_tree: None,
+            metaplex_metadata: false,
+            pending_escrow: 0,
+        }
+    }
 }
 
 impl ProjectAccount {
@@ -64,30 +300,158 @@ impl ProjectAccount {
     pub fn is_admin(&self, key: &Pubkey) -> bool {
         self.authority == *key
     }
+
+    /// Account size for a base_uri of `base_uri_len` bytes, never below the default size
+    pub fn space_for(base_uri_len: usize) -> usize {
+        NON_TRANSFERABLE_PROJECT_SIZE + (4 + base_uri_len).saturating_sub(BASE_URI_RESERVED_SPACE)
+    }
+
+    /// Check if any of `flags` is paused
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+
+    /// Check if the key is admin or guardian
+    pub fn is_admin_or_guardian(&self, key: &Pubkey) -> bool {
+        self.authority == *key || self.guardian == Some(*key)
+    }
+
+    /// Check if the key holds `permission`
+    /// Admin holds every permission, operators hold `permissions::OPERATOR_DEFAULT`,
+    /// anything else needs a matching role
+    pub fn has_permission(&self, key: &Pubkey, role: Option<&RoleAccount>, permission: u32) -> bool {
+        self.is_admin(key)
+            || (self.operators.contains(key) && permissions::OPERATOR_DEFAULT & permission == permission)
+            || role.is_some_and(|role| role.project_id == self.project_id && role.grants(key, permission))
+    }
+}
+
+/// One withdraw recipient and its share in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct RevenueShare {
+    pub recipient: Pubkey,
+    pub bps: u16,
+}
+
+/// Role holder PDA, seeded by project and holder key - one per holder, no cap on holders
+#[account]
+#[derive(Default)]
+pub struct RoleAccount {
+    pub project_id: u64,
+    pub holder: Pubkey,
+    pub permissions: u32,       // Bitflags from `permissions`
+    pub bump: u8,
+}
+
+impl RoleAccount {
+    /// Check if this role belongs to `key` and includes `permission`
+    pub fn grants(&self, key: &Pubkey, permission: u32) -> bool {
+        self.holder == *key && self.permissions & permission == permission
+    }
+}
+
+
+
+/// Lifecycle of a paid mint order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum OrderStatus {
+    #[default]
+    Pending,
+    Fulfilled,
+    Refunded,
+    Cancelled,
 }
 
+/// Order record created by authorize_mint, seeded by project and hash_seed(order_id)
+#[account]
+#[derive(Default)]
+pub struct OrderAccount {
+    pub payer: Pubkey,          // Who paid mint_price
+    pub wallet: Pubkey,         // Beneficiary wallet that receives the DID
+    pub amount: u64,            // Lamports paid, or token base units for SPL orders
+    pub slot: u64,              // Slot the order was paid in
+    pub status: OrderStatus,
+    pub bump: u8,
+    pub payment_mint: Option<Pubkey>, // None = SOL, Some = SPL token held in the project vault
+}
 
+impl OrderAccount {
+    /// Take a resolved order's payment out of the pending escrow it was counted in
+    /// SPL orders are counted on their payment mint config, which must be passed in
+    /// Saturating: orders paid before escrow was tracked were never counted
+    pub fn release_escrow(
+        &self,
+        project: &mut ProjectAccount,
+        payment_mint_config: Option<&mut PaymentMintAccount>,
+    ) -> Result<()> {
+        match self.payment_mint {
+            None => project.pending_escrow = project.pending_escrow.saturating_sub(self.amount),
+            Some(mint) => {
+                let Some(config) = payment_mint_config else {
+                    return err!(crate::error::ErrorCode::InvalidAccountData);
+                };
+                require!(config.mint == mint, crate::error::ErrorCode::PaymentMintMismatch);
+                config.pending_escrow = config.pending_escrow.saturating_sub(self.amount);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Accepted SPL payment mint and its price, one PDA per project + mint
+#[account]
+#[derive(Default)]
+pub struct PaymentMintAccount {
+    pub mint: Pubkey,
+    pub price: u64,             // Price in token base units
+    pub bump: u8,
+    pub pending_escrow: u64,    // Tokens paid for orders still Pending, not withdrawable
+}
+
+/// Wallet -> DID mint index, per project (one DID per wallet)
+#[account]
+#[derive(Default)]
+pub struct WalletIndex {
+    pub mint: Pubkey,
+    pub bump: u8,
+}
+
+/// Blacklist entry, per project - existence of the PDA means the wallet / LDID is blocked
+#[account]
+#[derive(Default)]
+pub struct BlacklistEntry {
+    pub added_by: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
 
 // ============================================
 // Instruction Contexts (kept for compatibility)
 // ============================================
 
 #[derive(Accounts)]
-pub struct SetBaseURI<'info> {
+pub struct SetFeeRecipient<'info> {
     #[account(mut, has_one = authority)]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct SetFeeRecipient<'info> {
+pub struct SetFundDestination<'info> {
     #[account(mut, has_one = authority)]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct SetFundDestination<'info> {
+pub struct SetReclaimTimeout<'info> {
+    #[account(mut, has_one = authority)]
+    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRevenueShares<'info> {
     #[account(mut, has_one = authority)]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
     pub authority: Signer<'info>,
@@ -95,16 +459,20 @@ pub struct SetFundDestination<'info> {
 
 
 
-/// Admin or Operator can set mint price
+/// Admin or SET_PRICE role holder can set mint price
 #[derive(Accounts)]
 pub struct SetMintPriceContext<'info> {
     #[account()]
     pub authority: Signer<'info>,
     #[account(
         mut,
-        constraint = non_transferable_project.is_admin_or_operator(&authority.key()) @ crate::error::ErrorCode::Unauthorized
+        constraint = non_transferable_project.has_permission(&authority.key(), role.as_deref(), permissions::SET_PRICE) @ crate::error::ErrorCode::Unauthorized,
+        constraint = !non_transferable_project.is_paused(pause_flags::OPERATOR) @ crate::error::ErrorCode::ProgramPaused
     )]
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
+
+    /// Role granting the signer SET_PRICE (not needed for admin)
+    pub role: Option<Account<'info, RoleAccount>>,
 }
 
 /// Add/remove operator (admin only)
@@ -119,18 +487,6 @@ pub struct ManageOperator<'info> {
     pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
 }
 
-/// Transfer authority (current admin only)
-#[derive(Accounts)]
-pub struct TransferAuthority<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(
-        mut,
-        constraint = non_transferable_project.authority == authority.key() @ crate::error::ErrorCode::Unauthorized
-    )]
-    pub non_transferable_project: Box<Account<'info, ProjectAccount>>,
-}
-
 /// Admin or Operator can perform this action
 #[derive(Accounts)]
 pub struct AdminOrOperatorAction<'info> {
@@ -147,8 +503,38 @@ pub struct AdminOrOperatorAction<'info> {
 // Helper Functions
 // ============================================
 
+/// Project PDA seed after NON_TRANSFERABLE_PROJECT_PREFIX
+/// Empty for the legacy project - an empty seed derives the same address as no seed,
+/// so project 0 is the original singleton at [NON_TRANSFERABLE_PROJECT_PREFIX]
+pub fn project_id_seed(project_id: u64) -> Vec<u8> {
+    if project_id == LEGACY_PROJECT_ID {
+        Vec::new()
+    } else {
+        project_id.to_le_bytes().to_vec()
+    }
+}
+
+/// Seed that scopes the collection and DID mints to a project, empty for the legacy project
+/// so its collection mint and already issued DIDs keep their addresses
+pub fn project_scope_seed(project_id: u64, project: &Pubkey) -> Vec<u8> {
+    if project_id == LEGACY_PROJECT_ID {
+        Vec::new()
+    } else {
+        project.to_bytes().to_vec()
+    }
+}
+
 pub fn hash_seed(seed: &str) -> Vec<u8> {
     let mut hasher = Sha256::new();
     hasher.update(seed.as_bytes());
     hasher.finalize().to_vec()
 }
+
+/// LDID blacklist seed, the 32 bytes of the merkle root so every spelling maps to one PDA
+/// A root that isn't valid hex is hashed as given, the handlers reject it
+pub fn ldid_seed(merkle_root: &str) -> Vec<u8> {
+    match crate::utils::hex_to_bytes32(merkle_root) {
+        Ok(bytes) => bytes.to_vec(),
+        Err(_) => hash_seed(merkle_root),
+    }
+}
@@ -1,22 +1,126 @@
-use sha2::{Digest, Sha256};
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+/// Convert hex string (optional 0x prefix) to [u8; 32]
+/// Rejects anything that is not exactly 32 bytes of hex
+pub fn hex_to_bytes32(hex_str: &str) -> Result<[u8; 32]> {
+    let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
+
+    require!(
+        hex_str.len() == 64 && hex_str.bytes().all(|b| b.is_ascii_hexdigit()),
+        ErrorCode::InvalidMerkleRoot
+    );
 
-/// Convert hex string to [u8; 32]
-pub fn hex_to_bytes32(hex_str: &str) -> [u8; 32] {
     let mut result = [0u8; 32];
-    let hex_str = hex_str.trim_start_matches("0x");
-
-    if hex_str.len() >= 64 {
-        for i in 0..32 {
-            if let Ok(byte) = u8::from_str_radix(&hex_str[i * 2..i * 2 + 2], 16) {
-                result[i] = byte;
-            }
-        }
-    } else {
-        // If not valid hex, use hash
-        let mut hasher = Sha256::new();
-        hasher.update(hex_str.as_bytes());
-        let hash = hasher.finalize();
-        result.copy_from_slice(&hash[..32]);
+    for (i, byte) in result.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex_str[i * 2..i * 2 + 2], 16)
+            .map_err(|_| ErrorCode::InvalidMerkleRoot)?;
+    }
+    Ok(result)
+}
+
+/// Canonical spelling of a 32 byte hex string: lowercase, no 0x prefix
+/// Uris, stored metadata and PDA seeds all use it, so one root has one spelling
+pub fn canonical_hex32(hex_str: &str) -> Result<String> {
+    hex_to_bytes32(hex_str)?;
+    Ok(hex_str.strip_prefix("0x").unwrap_or(hex_str).to_ascii_lowercase())
+}
+
+/// Verify that the instruction right before the current one is an Ed25519
+/// precompile check of exactly one signature over `message`, and return the signer key
+/// Offsets must point into the precompile instruction itself (index u16::MAX)
+pub fn verify_ed25519_ix(instructions_sysvar: &AccountInfo, message: &[u8]) -> Result<Pubkey> {
+    use anchor_lang::solana_program::ed25519_program;
+    use anchor_lang::solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked,
+    };
+
+    const HEADER_LEN: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const PUBKEY_LEN: usize = 32;
+    const SIGNATURE_LEN: usize = 64;
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, ErrorCode::InvalidDataProvided);
+
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+    require!(
+        ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+        ErrorCode::InvalidDataProvided
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= HEADER_LEN + OFFSETS_LEN && data[0] == 1,
+        ErrorCode::InvalidDataProvided
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = HEADER_LEN;
+    let signature_offset = read_u16(offsets) as usize;
+    let signature_ix_index = read_u16(offsets + 2);
+    let public_key_offset = read_u16(offsets + 4) as usize;
+    let public_key_ix_index = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_size = read_u16(offsets + 10) as usize;
+    let message_ix_index = read_u16(offsets + 12);
+
+    require!(
+        signature_ix_index == u16::MAX
+            && public_key_ix_index == u16::MAX
+            && message_ix_index == u16::MAX,
+        ErrorCode::InvalidDataProvided
+    );
+    require!(
+        data.len() >= signature_offset + SIGNATURE_LEN
+            && data.len() >= public_key_offset + PUBKEY_LEN
+            && data.len() >= message_offset + message_size,
+        ErrorCode::InvalidDataProvided
+    );
+
+    require!(
+        &data[message_offset..message_offset + message_size] == message,
+        ErrorCode::SignatureVerificationFailed
+    );
+
+    let signer = Pubkey::try_from(&data[public_key_offset..public_key_offset + PUBKEY_LEN])
+        .map_err(|_| ErrorCode::InvalidDataProvided)?;
+    Ok(signer)
+}
+
+/// Close a wallet index PDA that points at `mint`, rent to `destination`
+/// The index doesn't exist for DIDs minted before wallet indexes, and belongs to another
+/// DID if it points elsewhere - both are left alone
+pub fn close_wallet_index<'info>(
+    wallet_index: &AccountInfo<'info>,
+    mint: &Pubkey,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    if wallet_index.data_is_empty() {
+        return Ok(());
+    }
+    require!(
+        wallet_index.owner == &crate::ID,
+        ErrorCode::InvalidAccountData
+    );
+
+    let index = {
+        let data = wallet_index.try_borrow_data()?;
+        crate::state::WalletIndex::try_deserialize(&mut &data[..])?
+    };
+    if index.mint != *mint {
+        return Ok(());
     }
-    result
+
+    let lamports = wallet_index.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ErrorCode::Overflow)?;
+    **wallet_index.try_borrow_mut_lamports()? = 0;
+    wallet_index.assign(&anchor_lang::system_program::ID);
+    wallet_index.resize(0)?;
+
+    Ok(())
 }
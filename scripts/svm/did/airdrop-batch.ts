@@ -0,0 +1,246 @@
+/**
+ * Batch Airdrop Script
+ *
+ * Mints many DIDs through airdrop_batch. Entries are packed into versioned
+ * transactions whose accounts are loaded from an address lookup table, so
+ * each transaction carries several DIDs instead of one.
+ *
+ * Entries that don't fit in a transaction's compute budget stay Pending on-chain
+ * and are retried in the next round.
+ *
+ * Usage:
+ *   ts-node airdrop-batch.ts <network> <project_id> <entries.json> [batch_size]
+ *   entries.json: [{ "orderId": "...", "wallet": "...", "merkleRoot": "..." }, ...]
+ */
+
+import { Program, AnchorProvider, Wallet, web3, BN } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import * as fs from "fs";
+import { getNetworkConfig, getExplorerLink } from "../../config";
+import { createHash } from "crypto";
+import { findCollectionMintPda, findDidMintPda, findLdidBlacklistPda, findProjectPda } from "./pda";
+
+const {
+  Connection,
+  PublicKey,
+  Keypair,
+  ComputeBudgetProgram,
+  SystemProgram,
+  AddressLookupTableProgram,
+  TransactionMessage,
+  VersionedTransaction,
+} = web3;
+
+const network = process.argv[2] || "devnet";
+const projectId = new BN(process.argv[3] || "0");
+const entriesPath = process.argv[4] || "./airdrop-entries.json";
+const batchSize = parseInt(process.argv[5] || "6", 10);
+const config = getNetworkConfig(network);
+
+const PROGRAM_ID = new PublicKey(config.programId);
+const RPC_URL = config.rpcUrl;
+
+// Lookup table extend instructions are capped by transaction size
+const ALT_EXTEND_CHUNK = 20;
+
+interface AirdropEntry {
+  orderId: string;
+  wallet: string;
+  merkleRoot: string;
+}
+
+function sha256(value: string): Buffer {
+  return createHash("sha256").update(value).digest();
+}
+
+function findNonTransferableProject(): web3.PublicKey {
+  return findProjectPda(PROGRAM_ID, projectId);
+}
+
+function findPda(seeds: Buffer[]): web3.PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(seeds, PROGRAM_ID);
+  return pda;
+}
+
+/**
+ * Remaining accounts for one entry, in the order airdrop_batch expects:
+ * order, wallet_index, wallet_blacklist, ldid_blacklist, nft_mint, user_account, user_token_account
+ */
+function entryAccounts(project: web3.PublicKey, entry: AirdropEntry): web3.AccountMeta[] {
+  const wallet = new PublicKey(entry.wallet);
+  const nftMint = findDidMintPda(PROGRAM_ID, projectId, entry.orderId);
+  const userTokenAccount = getAssociatedTokenAddressSync(nftMint, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+  return [
+    { pubkey: findPda([Buffer.from("nt-order-v5"), project.toBuffer(), sha256(entry.orderId)]), isSigner: false, isWritable: true },
+    { pubkey: findPda([Buffer.from("nt-wallet-idx-v5"), project.toBuffer(), wallet.toBuffer()]), isSigner: false, isWritable: true },
+    { pubkey: findPda([Buffer.from("nt-blk-wallet-v5"), project.toBuffer(), wallet.toBuffer()]), isSigner: false, isWritable: false },
+    { pubkey: findLdidBlacklistPda(PROGRAM_ID, projectId, entry.merkleRoot), isSigner: false, isWritable: false },
+    { pubkey: nftMint, isSigner: false, isWritable: true },
+    { pubkey: wallet, isSigner: false, isWritable: true },
+    { pubkey: userTokenAccount, isSigner: false, isWritable: true },
+  ];
+}
+
+/**
+ * Create a lookup table holding every address of one batch and wait until it is usable
+ */
+async function createLookupTable(
+  connection: web3.Connection,
+  payer: web3.Keypair,
+  addresses: web3.PublicKey[]
+): Promise<web3.AddressLookupTableAccount> {
+  const slot = await connection.getSlot("finalized");
+  const [createIx, lookupTable] = AddressLookupTableProgram.createLookupTable({
+    authority: payer.publicKey,
+    payer: payer.publicKey,
+    recentSlot: slot,
+  });
+
+  const unique = [...new Map(addresses.map((a) => [a.toBase58(), a])).values()];
+  const instructions: web3.TransactionInstruction[][] = [[createIx]];
+  for (let i = 0; i < unique.length; i += ALT_EXTEND_CHUNK) {
+    instructions.push([
+      AddressLookupTableProgram.extendLookupTable({
+        lookupTable,
+        authority: payer.publicKey,
+        payer: payer.publicKey,
+        addresses: unique.slice(i, i + ALT_EXTEND_CHUNK),
+      }),
+    ]);
+  }
+
+  for (const ixs of instructions) {
+    await sendV0(connection, payer, ixs, []);
+  }
+
+  // A lookup table can only be used from the slot after its last extension
+  const extendedAt = await connection.getSlot("confirmed");
+  while ((await connection.getSlot("confirmed")) <= extendedAt) {
+    await new Promise((resolve) => setTimeout(resolve, 400));
+  }
+
+  const table = await connection.getAddressLookupTable(lookupTable);
+  if (!table.value) {
+    throw new Error(`Lookup table ${lookupTable.toBase58()} not found`);
+  }
+  return table.value;
+}
+
+async function sendV0(
+  connection: web3.Connection,
+  payer: web3.Keypair,
+  instructions: web3.TransactionInstruction[],
+  lookupTables: web3.AddressLookupTableAccount[]
+): Promise<string> {
+  const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash("confirmed");
+  const message = new TransactionMessage({
+    payerKey: payer.publicKey,
+    recentBlockhash: blockhash,
+    instructions,
+  }).compileToV0Message(lookupTables);
+
+  const tx = new VersionedTransaction(message);
+  tx.sign([payer]);
+
+  const signature = await connection.sendTransaction(tx);
+  await connection.confirmTransaction({ signature, blockhash, lastValidBlockHeight }, "confirmed");
+  return signature;
+}
+
+async function main() {
+  console.log("========================================");
+  console.log(`RNS DID Batch Airdrop - ${network.toUpperCase()}`);
+  console.log("========================================\n");
+
+  const walletPath = process.env.HOME + "/.config/solana/id.json";
+  const secretKey = JSON.parse(fs.readFileSync(walletPath, "utf-8"));
+  const adminWallet = Keypair.fromSecretKey(new Uint8Array(secretKey));
+
+  const connection = new Connection(RPC_URL, "confirmed");
+  const wallet = new Wallet(adminWallet);
+  const provider = new AnchorProvider(connection, wallet, { commitment: "confirmed" });
+
+  const idlPath = "./target/idl/legaldid.json";
+  const idl = JSON.parse(fs.readFileSync(idlPath, "utf-8"));
+  const program = new Program(idl, provider);
+
+  const project = findNonTransferableProject();
+  const collectionMint = findCollectionMintPda(PROGRAM_ID, projectId);
+  const entries: AirdropEntry[] = JSON.parse(fs.readFileSync(entriesPath, "utf-8"));
+
+  console.log("Network:", network);
+  console.log("Program ID:", PROGRAM_ID.toBase58());
+  console.log("Admin Wallet:", adminWallet.publicKey.toBase58());
+  console.log("Project:", project.toBase58());
+  console.log("Entries:", entries.length, "| Batch size:", batchSize);
+  console.log("");
+
+  let pending = entries;
+  while (pending.length > 0) {
+    const batch = pending.slice(0, batchSize);
+    const remainingAccounts = batch.flatMap((entry) => entryAccounts(project, entry));
+
+    const airdropIx = await program.methods
+      .airdropBatch(
+        batch.map((entry) => ({
+          orderId: entry.orderId,
+          wallet: new PublicKey(entry.wallet),
+          merkleRoot: entry.merkleRoot,
+        }))
+      )
+      .accounts({
+        authority: adminWallet.publicKey,
+        nonTransferableProject: project,
+        role: null,
+        collectionMint,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .instruction();
+
+    const lookupTable = await createLookupTable(connection, adminWallet, [
+      PROGRAM_ID,
+      project,
+      collectionMint,
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      TOKEN_2022_PROGRAM_ID,
+      SystemProgram.programId,
+      ...remainingAccounts.map((meta) => meta.pubkey),
+    ]);
+
+    const signature = await sendV0(
+      connection,
+      adminWallet,
+      [ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }), airdropIx],
+      [lookupTable]
+    );
+
+    // Count what was actually minted - the program stops early when compute runs low
+    const orderInfos = await connection.getMultipleAccountsInfo(
+      batch.map((entry) => entryAccounts(project, entry)[0].pubkey)
+    );
+    const minted = batch.filter((_, i) => {
+      const order = orderInfos[i];
+      return order && (program.coder.accounts as any).decode("orderAccount", order.data).status.fulfilled !== undefined;
+    });
+
+    console.log(`✅ Minted ${minted.length}/${batch.length}: ${getExplorerLink(signature, network, "tx")}`);
+    if (minted.length === 0) {
+      throw new Error("No DIDs minted in this batch - lower the batch size");
+    }
+
+    const mintedIds = new Set(minted.map((entry) => entry.orderId));
+    pending = pending.filter((entry) => !mintedIds.has(entry.orderId));
+  }
+
+  console.log("\n🎉 All", entries.length, "DIDs minted");
+}
+
+main().catch(console.error);
@@ -19,6 +19,10 @@ import { Program, AnchorProvider, Wallet } from '@coral-xyz/anchor';
 import * as bs58 from 'bs58';
 import * as dotenv from 'dotenv';
 import { getNetworkConfig, getExplorerLink, NetworkConfig } from '../../config';
+import { findOrderPda, findProjectPda, projectIdFromEnv } from './pda';
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 dotenv.config();
 
@@ -44,10 +48,14 @@ export class AuthorizeMintManager {
         
         // 计算项目PDA
         const programId = new PublicKey(this.config.programId);
-        [this.projectPDA] = PublicKey.findProgramAddressSync(
-            [Buffer.from("nt-proj-v5")],
-            programId
-        );
+        this.projectPDA = findProjectPda(programId, PROJECT_ID);
+    }
+    
+    /**
+     * 计算订单PDA (order_id 哈希作为种子)
+     */
+    private getOrderPDA(orderId: string): PublicKey {
+        return findOrderPda(new PublicKey(this.config.programId), PROJECT_ID, orderId);
     }
     
     /**
@@ -151,10 +159,11 @@ export class AuthorizeMintManager {
             console.log("📤 发送 authorize_mint 交易...");
             
             const tx = await (this.program.methods as any)
-                .authorizeMint(orderId)
+                .authorizeMint(orderId, userWallet.publicKey)
                 .accounts({
                     payer: userWallet.publicKey,
                     nonTransferableProject: this.projectPDA,
+                    order: this.getOrderPDA(orderId),
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
@@ -2,6 +2,10 @@ import { Program, AnchorProvider, Wallet, web3 } from "@coral-xyz/anchor";
 import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import * as fs from "fs";
 import { getNetworkConfig, getExplorerLink } from "../../config";
+import { findCollectionMintPda, findProjectPda, projectIdFromEnv } from "./pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 const {
   Connection,
@@ -21,19 +25,11 @@ const RPC_URL = config.rpcUrl;
 
 // PDA calculation functions (v5 version - with Collection + Metadata)
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function getProjectMintAddress(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-project-mint-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findCollectionMintPda(PROGRAM_ID, PROJECT_ID);
 }
 
 async function main() {
@@ -104,9 +100,11 @@ async function main() {
 
     const tx = await program.methods
       .initialize({
+        projectId: PROJECT_ID,
         name: "Legal DID",
         symbol: "LDID",
         baseUri: `${domain}api/v2/portal/identity/nft/`,
+        metaplexMetadata: false,
       })
       .accounts({
         authority: adminWallet.publicKey,
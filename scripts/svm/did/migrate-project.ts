@@ -0,0 +1,75 @@
+/**
+ * Migrate the ProjectAccount in place to the current layout version
+ *
+ * Keeps the same project PDA and collection mint, so existing DIDs stay in their collection.
+ * Replaces the close-and-reinit flow of migrate-to-v5.ts / reinit-project.ts.
+ *
+ * Usage: npx ts-node scripts/svm/did/migrate-project.ts <network> <project_id>
+ */
+
+import { Program, AnchorProvider, Wallet, web3, BN } from "@coral-xyz/anchor";
+import * as fs from "fs";
+import { getNetworkConfig, getExplorerLink } from "../../config";
+import { findProjectPda } from "./pda";
+
+const { Connection, PublicKey, Keypair, SystemProgram } = web3;
+
+const network = process.argv[2] || "devnet";
+const projectId = new BN(process.argv[3] || "0");
+const config = getNetworkConfig(network);
+
+const PROGRAM_ID = new PublicKey(config.programId);
+const RPC_URL = config.rpcUrl;
+
+function findNonTransferableProject(): web3.PublicKey {
+  return findProjectPda(PROGRAM_ID, projectId);
+}
+
+async function main() {
+  console.log("========================================");
+  console.log(`Migrate Project Account - ${network.toUpperCase()}`);
+  console.log("========================================\n");
+
+  const walletPath = process.env.HOME + "/.config/solana/id.json";
+  const secretKey = JSON.parse(fs.readFileSync(walletPath, "utf-8"));
+  const adminWallet = Keypair.fromSecretKey(new Uint8Array(secretKey));
+
+  const connection = new Connection(RPC_URL, "confirmed");
+  const wallet = new Wallet(adminWallet);
+  const provider = new AnchorProvider(connection, wallet, { commitment: "confirmed" });
+
+  const idlPath = "./target/idl/legaldid.json";
+  const idl = JSON.parse(fs.readFileSync(idlPath, "utf-8"));
+  const program = new Program(idl, provider);
+
+  const projectPda = findNonTransferableProject();
+
+  console.log("Network:", network);
+  console.log("Program ID:", PROGRAM_ID.toBase58());
+  console.log("Admin Wallet:", adminWallet.publicKey.toBase58());
+  console.log("Project ID:", projectId.toString());
+  console.log("Project PDA:", projectPda.toBase58());
+  console.log("");
+
+  const account = await connection.getAccountInfo(projectPda);
+  if (!account) {
+    console.log("❌ Project account not found");
+    return;
+  }
+  console.log("Current size:", account.data.length, "bytes");
+
+  const tx = await program.methods
+    .migrateProject(projectId)
+    .accounts({
+      authority: adminWallet.publicKey,
+      nonTransferableProject: projectPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  const migrated = await connection.getAccountInfo(projectPda);
+  console.log("✅ Project migrated, new size:", migrated?.data.length, "bytes");
+  console.log("Transaction:", getExplorerLink(tx, network, "tx"));
+}
+
+main().catch(console.error);
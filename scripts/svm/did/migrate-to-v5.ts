@@ -9,6 +9,10 @@ import { Program, AnchorProvider, Wallet, web3 } from "@coral-xyz/anchor";
 import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import * as fs from "fs";
 import { getNetworkConfig } from "../../config";
+import { findCollectionMintPda, findProjectPda, projectIdFromEnv } from "./pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 const { Connection, PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } = web3;
 
@@ -40,15 +44,9 @@ async function main() {
   const idl = JSON.parse(fs.readFileSync(idlPath, "utf-8"));
   const program = new Program(idl, provider);
 
-  const [projectPda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
+  const projectPda = findProjectPda(PROGRAM_ID, PROJECT_ID);
 
-  const [collectionMint] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-project-mint-v5")],
-    PROGRAM_ID
-  );
+  const collectionMint = findCollectionMintPda(PROGRAM_ID, PROJECT_ID);
 
   console.log("Project PDA:", projectPda.toBase58());
   console.log("Collection Mint:", collectionMint.toBase58());
@@ -6,6 +6,10 @@ import {
 } from "@solana/spl-token";
 import * as fs from "fs";
 import { getNetworkConfig, getExplorerLink } from "../../config";
+import { findCollectionMintPda, findDidMintPda, findProjectPda, projectIdFromEnv } from "./pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 const {
   Connection,
@@ -35,27 +39,15 @@ const MINT_TO_ADDRESS = new PublicKey(recipientAddress);
 
 // PDA calculation functions
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function findCollectionMint(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-project-mint-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findCollectionMintPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function getNftMintAddress(orderId: string): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-nft-mint-v5"), Buffer.from(orderId)],
-    PROGRAM_ID
-  );
-  return pda;
+  return findDidMintPda(PROGRAM_ID, PROJECT_ID, orderId);
 }
 
 async function main() {
@@ -6,7 +6,10 @@ import {
 } from "@solana/spl-token";
 import * as fs from "fs";
 import { getNetworkConfig, getExplorerLink } from "../../config";
-import { createHash } from "crypto";
+import { findCollectionMintPda, findDidMintPda, findOrderPda, findProjectPda, projectIdFromEnv } from "./pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 const {
   Connection,
@@ -35,29 +38,21 @@ const merkleRoot = process.argv[5] || "2d852b3c21e923484a93d3a980a45b7571e89552d
 
 // PDA calculation functions (v5 version - with Collection + Metadata)
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function findCollectionMint(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-project-mint-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findCollectionMintPda(PROGRAM_ID, PROJECT_ID);
 }
 
 // NFT Mint PDA is derived from order_id hash (to support long UUIDs)
 function getNftMintAddress(orderId: string): web3.PublicKey {
-  const hash = createHash('sha256').update(orderId).digest();
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-nft-mint-v5"), hash],
-    PROGRAM_ID
-  );
-  return pda;
+  return findDidMintPda(PROGRAM_ID, PROJECT_ID, orderId);
+}
+
+// Order PDA is derived from order_id hash (created by authorize_mint)
+function getOrderAddress(orderId: string): web3.PublicKey {
+  return findOrderPda(PROGRAM_ID, PROJECT_ID, orderId);
 }
 
 async function main() {
@@ -104,6 +99,7 @@ async function main() {
   
   // NFT Mint PDA is derived from order_id
   const nonTransferableNftMint = getNftMintAddress(orderId);
+  const order = getOrderAddress(orderId);
   const userTokenAccount = getAssociatedTokenAddressSync(
     nonTransferableNftMint,
     MINT_TO_ADDRESS,
@@ -115,6 +111,7 @@ async function main() {
   console.log("  Project:", nonTransferableProject.toBase58());
   console.log("  Collection Mint:", collectionMint.toBase58());
   console.log("  NFT Mint (order_id=" + orderId + "):", nonTransferableNftMint.toBase58());
+  console.log("  Order:", order.toBase58());
   console.log("  User Token Account:", userTokenAccount.toBase58());
   console.log("");
   console.log("✅ Project initialized\n");
@@ -132,6 +129,7 @@ async function main() {
       .accounts({
         authority: adminWallet.publicKey,
         nonTransferableProject: nonTransferableProject,
+        order: order,
         nonTransferableNftMint: nonTransferableNftMint,
         userAccount: MINT_TO_ADDRESS,
         userTokenAccount: userTokenAccount,
@@ -0,0 +1,84 @@
+/**
+ * LegalDID PDA helpers, shared by the DID scripts
+ *
+ * Project 0 is the legacy singleton project created before multi-project support.
+ * Its PDAs have no project component: the program uses an empty seed for it,
+ * which derives the same address as leaving the seed out.
+ */
+
+import { BN, web3 } from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+
+export const LEGACY_PROJECT_ID = 0;
+
+export const NON_TRANSFERABLE_PROJECT_PREFIX = "nt-proj-v5";
+export const NON_TRANSFERABLE_PROJECT_MINT_PREFIX = "nt-project-mint-v5";
+export const NON_TRANSFERABLE_NFT_MINT_PREFIX = "nt-nft-mint-v5";
+export const NON_TRANSFERABLE_ORDER_PREFIX = "nt-order-v5";
+export const BLOCKED_LDID_PREFIX = "nt-blk-ldid-v5";
+
+/** Project id from PROJECT_ID, defaults to the legacy project */
+export function projectIdFromEnv(): BN {
+  return new BN(process.env.PROJECT_ID || LEGACY_PROJECT_ID);
+}
+
+export function hashSeed(value: string): Buffer {
+  return createHash("sha256").update(value).digest();
+}
+
+/** Seed after the project prefix, empty for the legacy project */
+export function projectIdSeed(projectId: BN | number): Buffer {
+  const id = new BN(projectId);
+  return id.eqn(LEGACY_PROJECT_ID) ? Buffer.alloc(0) : id.toArrayLike(Buffer, "le", 8);
+}
+
+/** Seed scoping the collection and DID mints to a project, empty for the legacy project */
+export function projectScopeSeed(projectId: BN | number, project: web3.PublicKey): Buffer {
+  return new BN(projectId).eqn(LEGACY_PROJECT_ID) ? Buffer.alloc(0) : project.toBuffer();
+}
+
+export function findProjectPda(programId: web3.PublicKey, projectId: BN | number): web3.PublicKey {
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(NON_TRANSFERABLE_PROJECT_PREFIX), projectIdSeed(projectId)],
+    programId
+  )[0];
+}
+
+export function findCollectionMintPda(programId: web3.PublicKey, projectId: BN | number): web3.PublicKey {
+  const project = findProjectPda(programId, projectId);
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(NON_TRANSFERABLE_PROJECT_MINT_PREFIX), projectScopeSeed(projectId, project)],
+    programId
+  )[0];
+}
+
+export function findDidMintPda(programId: web3.PublicKey, projectId: BN | number, orderId: string): web3.PublicKey {
+  const project = findProjectPda(programId, projectId);
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(NON_TRANSFERABLE_NFT_MINT_PREFIX), projectScopeSeed(projectId, project), hashSeed(orderId)],
+    programId
+  )[0];
+}
+
+/** Order PDA, scoped by the project account for every project including the legacy one */
+export function findOrderPda(programId: web3.PublicKey, projectId: BN | number, orderId: string): web3.PublicKey {
+  const project = findProjectPda(programId, projectId);
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(NON_TRANSFERABLE_ORDER_PREFIX), project.toBuffer(), hashSeed(orderId)],
+    programId
+  )[0];
+}
+
+/** Canonical merkle root spelling used on-chain: lowercase hex, no 0x prefix */
+export function canonicalMerkleRoot(merkleRoot: string): string {
+  return merkleRoot.replace(/^0x/, "").toLowerCase();
+}
+
+/** LDID blacklist PDA, seeded from the 32 bytes of the merkle root */
+export function findLdidBlacklistPda(programId: web3.PublicKey, projectId: BN | number, merkleRoot: string): web3.PublicKey {
+  const project = findProjectPda(programId, projectId);
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(BLOCKED_LDID_PREFIX), project.toBuffer(), Buffer.from(canonicalMerkleRoot(merkleRoot), "hex")],
+    programId
+  )[0];
+}
@@ -9,6 +9,10 @@
 
 import { Connection, PublicKey, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import { getNetworkConfig, getExplorerLink, NetworkConfig } from '../../config';
+import { findProjectPda, projectIdFromEnv } from './pda';
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 // 项目信息接口
 interface ProjectInfo {
@@ -34,10 +38,7 @@ export class OperatorQuery {
         this.programId = new PublicKey(this.config.programId);
         
         // 计算项目PDA
-        [this.projectPDA] = PublicKey.findProgramAddressSync(
-            [Buffer.from("nt-proj-v5")],
-            this.programId
-        );
+        this.projectPDA = findProjectPda(this.programId, PROJECT_ID);
     }
     
     /**
@@ -10,6 +10,10 @@
 import { Connection, PublicKey, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import * as process from 'process';
 import { getNetworkConfig, getExplorerLink, NetworkConfig } from '../../config';
+import { findProjectPda, projectIdFromEnv } from './pda';
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 // 类型定义
 interface ProjectInfo {
@@ -54,10 +58,7 @@ export class SolanaPriceQuery {
         this.programId = new PublicKey(this.config.programId);
         
         // 计算项目PDA
-        [this.projectPDA] = PublicKey.findProgramAddressSync(
-            [Buffer.from("nt-proj-v5")],
-            this.programId
-        );
+        this.projectPDA = findProjectPda(this.programId, PROJECT_ID);
     }
     
     /**
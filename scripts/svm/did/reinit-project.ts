@@ -7,6 +7,10 @@ import { Program, AnchorProvider, Wallet, web3, BN } from "@coral-xyz/anchor";
 import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import * as fs from "fs";
 import { getNetworkConfig, getExplorerLink } from "../../config";
+import { findCollectionMintPda, findProjectPda, projectIdFromEnv } from "./pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 const {
   Connection,
@@ -26,19 +30,11 @@ const PROGRAM_ID = new PublicKey(config.programId);
 const RPC_URL = config.rpcUrl;
 
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function getProjectMintAddress(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-project-mint-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findCollectionMintPda(PROGRAM_ID, PROJECT_ID);
 }
 
 async function main() {
@@ -103,9 +99,11 @@ async function main() {
   console.log("\n🚀 Initializing new project account...");
   
   const initArgs = {
+    projectId: PROJECT_ID,
     name: "Legal DID",
     symbol: "LDID",
     baseUri: "https://api.rns.id/api/v2/portal/identity/nft/",
+    metaplexMetadata: false,
   };
 
   try {
@@ -18,6 +18,10 @@ import * as fs from 'fs';
 import * as path from 'path';
 import * as dotenv from 'dotenv';
 import { getNetworkConfig, getExplorerLink } from '../../config';
+import { findProjectPda, projectIdFromEnv } from './pda';
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 dotenv.config();
 
@@ -84,10 +88,7 @@ async function setFundDestination(network: string, destinationAddress: string):
     }
 
     // 计算项目 PDA
-    const [projectPDA] = PublicKey.findProgramAddressSync(
-        [Buffer.from("nt-proj-v5")],
-        programId
-    );
+    const projectPDA = findProjectPda(programId, PROJECT_ID);
 
     console.log(`📍 项目 PDA: ${projectPDA.toString()}`);
     console.log(`🏗️  程序 ID: ${programId.toString()}`);
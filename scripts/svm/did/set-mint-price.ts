@@ -21,6 +21,10 @@ import * as fs from 'fs';
 import * as path from 'path';
 import * as dotenv from 'dotenv';
 import { getNetworkConfig, getExplorerLink, NetworkConfig } from '../../config';
+import { findProjectPda, projectIdFromEnv } from './pda';
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 dotenv.config();
 
@@ -45,10 +49,7 @@ export class MintPriceManager {
         
         // 计算项目PDA
         const programId = new PublicKey(this.config.programId);
-        [this.projectPDA] = PublicKey.findProgramAddressSync(
-            [Buffer.from("nt-proj-v5")],
-            programId
-        );
+        this.projectPDA = findProjectPda(programId, PROJECT_ID);
     }
     
     /**
@@ -13,7 +13,10 @@ import {
 } from "@solana/spl-token";
 import * as fs from "fs";
 import { getNetworkConfig, getExplorerLink } from "../../config";
-import { createHash } from "crypto";
+import { findDidMintPda, findProjectPda, projectIdFromEnv } from "./pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 const { Connection, PublicKey, Keypair, ComputeBudgetProgram, SystemProgram } = web3;
 
@@ -28,21 +31,11 @@ const RPC_URL = config.rpcUrl;
 const orderId = process.argv[3] || "burn-test-1768805402";
 
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function getNftMintAddress(orderId: string): web3.PublicKey {
-  // Use SHA256 hash of order_id as seed
-  const orderIdHash = createHash('sha256').update(orderId).digest();
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-nft-mint-v5"), orderIdHash],
-    PROGRAM_ID
-  );
-  return pda;
+  return findDidMintPda(PROGRAM_ID, PROJECT_ID, orderId);
 }
 
 async function main() {
@@ -2,8 +2,8 @@
 /**
  * Legal DID - 转移 Authority 权限
  * 
- * ⚠️ 警告: 此操作不可逆！
- * 转移后，当前 Authority 将失去所有管理权限。
+ * 两步转移: 当前 Authority 提议 (propose_authority)，
+ * 新 Authority 需调用 accept_authority 确认后才生效。
  * 
  * 用法: npx ts-node scripts/svm/did/transfer-authority.ts <network> <new_authority_address>
  * 示例: npx ts-node scripts/svm/did/transfer-authority.ts devnet BossWa11etAddressXXXXXXXXXXXXXXXXXXXXXXXXXXXX
@@ -22,6 +22,10 @@ import * as fs from 'fs';
 import * as path from 'path';
 import * as dotenv from 'dotenv';
 import * as readline from 'readline';
+import { findProjectPda, projectIdFromEnv } from './pda';
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 dotenv.config();
 
@@ -142,10 +146,7 @@ async function transferAuthority(
         }
 
         // 计算项目 PDA
-        const [projectPDA] = PublicKey.findProgramAddressSync(
-            [Buffer.from("nt-proj-v5")],
-            programId
-        );
+        const projectPDA = findProjectPda(programId, PROJECT_ID);
 
         console.log(`📍 项目 PDA: ${projectPDA.toString()}`);
         console.log(`🏗️  程序 ID: ${programId.toString()}`);
@@ -231,7 +232,7 @@ async function transferAuthority(
 
         // 执行转移
         const tx = await program.methods
-            .transferAuthority(newAuthority)
+            .proposeAuthority(newAuthority)
             .accounts({
                 authority: currentAuthority.publicKey,
                 nonTransferableProject: projectPDA,
@@ -9,10 +9,15 @@
  */
 
 import { Program, AnchorProvider, Wallet, web3 } from "@coral-xyz/anchor";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import * as fs from "fs";
 import { getNetworkConfig, getExplorerLink } from "../../config";
+import { findCollectionMintPda, findProjectPda, projectIdFromEnv } from "./pda";
 
-const { Connection, PublicKey, Keypair } = web3;
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
+
+const { Connection, PublicKey, Keypair, SystemProgram } = web3;
 
 // Get network from command line args (default: devnet)
 const network = process.argv[2] || "devnet";
@@ -22,11 +27,7 @@ const PROGRAM_ID = new PublicKey(config.programId);
 const RPC_URL = config.rpcUrl;
 
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 async function main() {
@@ -62,6 +63,9 @@ async function main() {
     .accounts({
       nonTransferableProject: nonTransferableProject,
       authority: adminWallet.publicKey,
+      collectionMint: findCollectionMintPda(PROGRAM_ID, PROJECT_ID),
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
     })
     .signers([adminWallet])
     .rpc();
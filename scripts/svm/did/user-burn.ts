@@ -21,6 +21,10 @@ import * as fs from "fs";
 import bs58 from "bs58";
 import * as dotenv from "dotenv";
 import { getNetworkConfig, getExplorerLink } from "../../config";
+import { findDidMintPda, findProjectPda, projectIdFromEnv } from "./pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 // Load environment variables
 dotenv.config();
@@ -48,19 +52,11 @@ const ADMIN_ADDRESS = new PublicKey("2fuikT5C2YVctakxoBNQ23NjXzA4kY2cn36Sh6ws3pA
 // ==========================
 
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function getNftMintAddress(orderId: string): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-nft-mint-v5"), Buffer.from(orderId)],
-    PROGRAM_ID
-  );
-  return pda;
+  return findDidMintPda(PROGRAM_ID, PROJECT_ID, orderId);
 }
 
 async function main() {
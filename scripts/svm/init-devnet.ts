@@ -1,6 +1,10 @@
 import { Program, AnchorProvider, Wallet, web3 } from "@coral-xyz/anchor";
 import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import * as fs from "fs";
+import { findCollectionMintPda, findProjectPda, projectIdFromEnv } from "./did/pda";
+
+// Project to operate on, PROJECT_ID env var, defaults to the legacy project 0
+const PROJECT_ID = projectIdFromEnv();
 
 const {
   Connection,
@@ -19,19 +23,11 @@ const RPC_URL = "https://api.devnet.solana.com";
 
 // PDA calculation functions
 function findNonTransferableProject(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-proj-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findProjectPda(PROGRAM_ID, PROJECT_ID);
 }
 
 function getProjectMintAddress(): web3.PublicKey {
-  const [pda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("nt-project-mint-v5")],
-    PROGRAM_ID
-  );
-  return pda;
+  return findCollectionMintPda(PROGRAM_ID, PROJECT_ID);
 }
 
 async function main() {
@@ -85,9 +81,11 @@ async function main() {
 
     const tx = await program.methods
       .initialize({
+        projectId: PROJECT_ID,
         name: "Legal DID",
         symbol: "LDID",
         baseUri: `${domain}api/v2/portal/identity/nft/`,
+        metaplexMetadata: false,
       })
       .accounts({
         authority: adminWallet.publicKey,
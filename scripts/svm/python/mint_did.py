@@ -5,6 +5,8 @@ Solana Legal DID - Python Mint Script
 
 Usage:
     python mint_did.py <user_wallet> <order_id> [merkle_root]
+
+    PROJECT_ID 环境变量选择项目, 默认 0 (旧版单例项目)
     
 Example:
     python mint_did.py 7s3NWENLzKzL18yGfy4rQNYFQPNFhiHnXYSgjptEwhBg d275d072-21e1-48d3-b17c-e0855712b067
@@ -39,6 +41,14 @@ ASSOCIATED_TOKEN_PROGRAM_ID = Pubkey.from_string("ATokenGPvbdGVxr1b2hvZbsiqW5xWH
 NON_TRANSFERABLE_PROJECT_PREFIX = b"nt-proj-v5"
 NON_TRANSFERABLE_PROJECT_MINT_PREFIX = b"nt-project-mint-v5"
 NON_TRANSFERABLE_NFT_MINT_PREFIX = b"nt-nft-mint-v5"
+NON_TRANSFERABLE_ORDER_PREFIX = b"nt-order-v5"
+WALLET_INDEX_PREFIX = b"nt-wallet-idx-v5"
+BLOCKED_WALLET_PREFIX = b"nt-blk-wallet-v5"
+BLOCKED_LDID_PREFIX = b"nt-blk-ldid-v5"
+
+# 项目 ID, 0 为多项目之前创建的旧版单例项目, 其 PDA 不包含项目部分
+LEGACY_PROJECT_ID = 0
+PROJECT_ID = int(os.environ.get("PROJECT_ID", LEGACY_PROJECT_ID))
 
 # Airdrop 指令的 discriminator (从 IDL 获取)
 AIRDROP_DISCRIMINATOR = bytes([113, 173, 36, 238, 38, 152, 22, 117])
@@ -69,10 +79,30 @@ def get_admin_keypair():
         raise Exception(f"Wallet file not found: {wallet_path}")
 
 
+def hash_seed(value: str) -> bytes:
+    """长字符串种子 (order_id / merkle_root) 的 SHA256 哈希"""
+    return sha256(value.encode('utf-8')).digest()
+
+
+def canonical_merkle_root(merkle_root: str) -> str:
+    """链上使用的 merkle_root 规范形式: 小写十六进制, 无 0x 前缀"""
+    return merkle_root.removeprefix("0x").lower()
+
+
+def project_id_seed() -> bytes:
+    """Project PDA 的项目种子, 旧版项目为空 (空种子与不带种子推导出相同地址)"""
+    return b"" if PROJECT_ID == LEGACY_PROJECT_ID else PROJECT_ID.to_bytes(8, 'little')
+
+
+def project_scope_seed(project_pda: Pubkey) -> bytes:
+    """Collection / NFT Mint 的项目种子, 旧版项目为空以保留原有地址"""
+    return b"" if PROJECT_ID == LEGACY_PROJECT_ID else bytes(project_pda)
+
+
 def get_project_pda():
     """计算 Project PDA"""
     pda, bump = Pubkey.find_program_address(
-        [NON_TRANSFERABLE_PROJECT_PREFIX],
+        [NON_TRANSFERABLE_PROJECT_PREFIX, project_id_seed()],
         PROGRAM_ID
     )
     return pda, bump
@@ -80,8 +110,9 @@ def get_project_pda():
 
 def get_collection_mint_pda():
     """计算 Collection Mint PDA"""
+    project_pda, _ = get_project_pda()
     pda, bump = Pubkey.find_program_address(
-        [NON_TRANSFERABLE_PROJECT_MINT_PREFIX],
+        [NON_TRANSFERABLE_PROJECT_MINT_PREFIX, project_scope_seed(project_pda)],
         PROGRAM_ID
     )
     return pda, bump
@@ -89,9 +120,19 @@ def get_collection_mint_pda():
 
 def get_nft_mint_pda(order_id: str):
     """计算 NFT Mint PDA (使用 order_id 的 SHA256 哈希)"""
-    order_id_hash = sha256(order_id.encode('utf-8')).digest()
+    project_pda, _ = get_project_pda()
+    pda, bump = Pubkey.find_program_address(
+        [NON_TRANSFERABLE_NFT_MINT_PREFIX, project_scope_seed(project_pda), hash_seed(order_id)],
+        PROGRAM_ID
+    )
+    return pda, bump
+
+
+def get_project_scoped_pda(prefix: bytes, seed: bytes):
+    """计算以 Project 账户地址划分的 PDA (订单 / 钱包索引 / 黑名单)"""
+    project_pda, _ = get_project_pda()
     pda, bump = Pubkey.find_program_address(
-        [NON_TRANSFERABLE_NFT_MINT_PREFIX, order_id_hash],
+        [prefix, bytes(project_pda), seed],
         PROGRAM_ID
     )
     return pda, bump
@@ -141,14 +182,34 @@ def create_airdrop_instruction(
     data += len(merkle_root_bytes).to_bytes(4, 'little')
     data += merkle_root_bytes
     
+    order_pda, _ = get_project_scoped_pda(NON_TRANSFERABLE_ORDER_PREFIX, hash_seed(order_id))
+    wallet_index_pda, _ = get_project_scoped_pda(WALLET_INDEX_PREFIX, bytes(user_wallet))
+    wallet_blacklist_pda, _ = get_project_scoped_pda(BLOCKED_WALLET_PREFIX, bytes(user_wallet))
+    ldid_blacklist_pda, _ = get_project_scoped_pda(BLOCKED_LDID_PREFIX, bytes.fromhex(canonical_merkle_root(merkle_root)))
+
+    # 可选账户 (role / Metaplex) 不传时使用程序 ID 占位
+    none_account = AccountMeta(pubkey=PROGRAM_ID, is_signer=False, is_writable=False)
+
     # 构建账户列表（顺序必须与程序定义一致）
     accounts = [
         AccountMeta(pubkey=authority, is_signer=True, is_writable=True),
         AccountMeta(pubkey=project_pda, is_signer=False, is_writable=True),
+        none_account,  # role
+        AccountMeta(pubkey=order_pda, is_signer=False, is_writable=True),
+        none_account,  # payment_mint_config (SPL 订单才需要)
+        AccountMeta(pubkey=wallet_index_pda, is_signer=False, is_writable=True),
+        AccountMeta(pubkey=wallet_blacklist_pda, is_signer=False, is_writable=False),
+        AccountMeta(pubkey=ldid_blacklist_pda, is_signer=False, is_writable=False),
         AccountMeta(pubkey=nft_mint_pda, is_signer=False, is_writable=True),
         AccountMeta(pubkey=user_wallet, is_signer=False, is_writable=True),
         AccountMeta(pubkey=user_token_account, is_signer=False, is_writable=True),
         AccountMeta(pubkey=collection_mint, is_signer=False, is_writable=True),
+        none_account,  # nft_metadata
+        none_account,  # nft_master_edition
+        none_account,  # collection_metadata
+        none_account,  # collection_master_edition
+        none_account,  # token_metadata_program
+        none_account,  # sysvar_instructions
         AccountMeta(pubkey=ASSOCIATED_TOKEN_PROGRAM_ID, is_signer=False, is_writable=False),
         AccountMeta(pubkey=TOKEN_2022_PROGRAM_ID, is_signer=False, is_writable=False),
         AccountMeta(pubkey=SYSTEM_PROGRAM_ID, is_signer=False, is_writable=False),
@@ -174,6 +235,7 @@ async def mint_did(user_wallet: Pubkey, order_id: str, merkle_root: str = None):
     print("=" * 50)
     print(f"Network: {NETWORK}")
     print(f"Program ID: {PROGRAM_ID}")
+    print(f"Project ID: {PROJECT_ID}")
     print(f"User Wallet: {user_wallet}")
     print(f"Order ID: {order_id}")
     print(f"Merkle Root: {merkle_root}")
@@ -10,6 +10,7 @@ import {
 import { PublicKey, Keypair, LAMPORTS_PER_SOL } from '@solana/web3.js'
 import { assert } from 'chai'
 import * as borsh from 'borsh'
+import { createHash } from 'crypto'
 
 // Constants
 const ADMIN_WALLET = Keypair.generate()
@@ -17,6 +18,16 @@ const USER_WALLET = Keypair.generate()
 const FEE_RECIPIENT = Keypair.generate()
 
 const ATTESTATION_CONFIG_PREFIX = "attestation-config"
+const ATTESTATION_REQUEST_PREFIX = "attestation-request"
+const SCHEMA_RECORD_PREFIX = "attestation-schema"
+const CREDENTIAL_AUTHORITY_PREFIX = "attestation-authority"
+const CREDENTIAL_NAME = "legal-did-schemas-test"
+
+// SAS Program ID (official)
+const SAS_PROGRAM_ID = new PublicKey("22zoJMtdu4tQc2PzL74ZUT7FrwgB1Udec8DdW4yw4BdG")
+
+// SAS layout type codes for the field types used below
+const SAS_TYPE_CODES: Record<string, number> = { u16: 1, i64: 8, bool: 10, string: 12 }
 
 // Schema definitions - 模拟 SAS 的 Schema 结构
 interface SchemaDefinition {
@@ -89,6 +100,15 @@ describe("LegalAttestation - Multiple Schemas Test", () => {
     const program = workspace.LegalAttestation as Program<LegalAttestation>
 
     let configPda: PublicKey
+    let credentialAuthorityPda: PublicKey
+    let credentialPda: PublicKey
+    const schemaPdas: Record<string, PublicKey> = {}
+
+    const findSchemaRecordPda = (schema: PublicKey): PublicKey =>
+        PublicKey.findProgramAddressSync(
+            [Buffer.from(SCHEMA_RECORD_PREFIX), schema.toBuffer()],
+            program.programId
+        )[0]
 
     before(async () => {
         // Calculate PDA
@@ -140,6 +160,61 @@ describe("LegalAttestation - Multiple Schemas Test", () => {
 
             console.log("✅ Config initialized with fee:", attestationFee.toNumber() / LAMPORTS_PER_SOL, "SOL")
         })
+
+        it("should create the credential and all 6 schemas", async () => {
+            [credentialAuthorityPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from(CREDENTIAL_AUTHORITY_PREFIX)],
+                program.programId
+            );
+            [credentialPda] = PublicKey.findProgramAddressSync(
+                [Buffer.from("credential"), credentialAuthorityPda.toBuffer(), Buffer.from(CREDENTIAL_NAME)],
+                SAS_PROGRAM_ID
+            )
+
+            await program.methods
+                .createCredential(CREDENTIAL_NAME)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    config: configPda,
+                    credentialAuthority: credentialAuthorityPda,
+                    credential: credentialPda,
+                    sasProgram: SAS_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            for (const schema of SCHEMAS) {
+                const [schemaPda] = PublicKey.findProgramAddressSync(
+                    [Buffer.from("schema"), credentialPda.toBuffer(), Buffer.from(schema.name), Buffer.from([1])],
+                    SAS_PROGRAM_ID
+                )
+
+                await program.methods
+                    .createSchema(
+                        schema.name,
+                        schema.description,
+                        Buffer.from(schema.fields.map((field) => SAS_TYPE_CODES[field.type])),
+                        schema.fields.map((field) => field.name)
+                    )
+                    .accountsPartial({
+                        authority: ADMIN_WALLET.publicKey,
+                        config: configPda,
+                        credentialAuthority: credentialAuthorityPda,
+                        credential: credentialPda,
+                        schema: schemaPda,
+                        schemaRecord: findSchemaRecordPda(schemaPda),
+                        sasProgram: SAS_PROGRAM_ID,
+                        systemProgram: web3.SystemProgram.programId,
+                    })
+                    .signers([ADMIN_WALLET])
+                    .rpc()
+
+                schemaPdas[schema.name] = schemaPda
+            }
+
+            console.log("✅ Credential and schemas created:", Object.keys(schemaPdas).length)
+        })
     })
 
     describe("Schema Definitions", () => {
@@ -169,39 +244,50 @@ describe("LegalAttestation - Multiple Schemas Test", () => {
             console.log("=" .repeat(60))
 
             const userBalanceBefore = await provider.connection.getBalance(USER_WALLET.publicKey)
-            const feeRecipientBalanceBefore = await provider.connection.getBalance(FEE_RECIPIENT.publicKey)
+            let totalEscrowed = 0
 
             // Request each attestation type
             for (const schema of SCHEMAS) {
-                const mockSchemaId = Keypair.generate().publicKey
+                const schemaPda = schemaPdas[schema.name]
                 const requestId = `${schema.name}-${Date.now()}`
+                const [requestPda] = PublicKey.findProgramAddressSync(
+                    [
+                        Buffer.from(ATTESTATION_REQUEST_PREFIX),
+                        USER_WALLET.publicKey.toBuffer(),
+                        createHash("sha256").update(requestId).digest(),
+                    ],
+                    program.programId
+                )
                 
                 await program.methods
-                    .requestAttestation(mockSchemaId, requestId)
+                    .requestAttestation(schemaPda, requestId)
                     .accountsPartial({
                         user: USER_WALLET.publicKey,
                         config: configPda,
-                        feeRecipient: FEE_RECIPIENT.publicKey,
+                        schemaRecord: findSchemaRecordPda(schemaPda),
+                        request: requestPda,
                         systemProgram: web3.SystemProgram.programId,
                     })
                     .signers([USER_WALLET])
                     .rpc()
 
+                const request = await program.account.attestationRequest.fetch(requestPda)
+                totalEscrowed += request.amount.toNumber()
+
                 const data = USER_ATTESTATION_DATA[schema.name as keyof typeof USER_ATTESTATION_DATA]
                 console.log(`\n✅ Requested: ${schema.name}`)
                 console.log(`   Data: ${JSON.stringify(data)}`)
             }
 
             const userBalanceAfter = await provider.connection.getBalance(USER_WALLET.publicKey)
-            const feeRecipientBalanceAfter = await provider.connection.getBalance(FEE_RECIPIENT.publicKey)
 
             const totalUserPaid = (userBalanceBefore - userBalanceAfter) / LAMPORTS_PER_SOL
-            const totalFeeReceived = (feeRecipientBalanceAfter - feeRecipientBalanceBefore) / LAMPORTS_PER_SOL
+            const totalFeeReceived = totalEscrowed / LAMPORTS_PER_SOL
 
             console.log("\n" + "=".repeat(60))
             console.log("💰 Cost Summary:")
-            console.log(`   Total user paid: ${totalUserPaid.toFixed(4)} SOL (includes tx fees)`)
-            console.log(`   Total fees received: ${totalFeeReceived.toFixed(4)} SOL`)
+            console.log(`   Total user paid: ${totalUserPaid.toFixed(4)} SOL (includes tx fees and request rent)`)
+            console.log(`   Total fees escrowed: ${totalFeeReceived.toFixed(4)} SOL`)
             console.log(`   Fee per attestation: ${(totalFeeReceived / SCHEMAS.length).toFixed(4)} SOL`)
             console.log(`   USD cost @ $140/SOL: $${(totalUserPaid * 140).toFixed(2)}`)
         })
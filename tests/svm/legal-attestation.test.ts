@@ -9,6 +9,7 @@ import {
 } from '@coral-xyz/anchor'
 import { PublicKey, Keypair, LAMPORTS_PER_SOL } from '@solana/web3.js'
 import { assert } from 'chai'
+import { createHash } from 'crypto'
 
 // Constants
 const ADMIN_WALLET = Keypair.generate()
@@ -17,6 +18,11 @@ const USER_WALLET = Keypair.generate()
 const FEE_RECIPIENT = Keypair.generate()
 
 const ATTESTATION_CONFIG_PREFIX = "attestation-config"
+const ATTESTATION_REQUEST_PREFIX = "attestation-request"
+const SCHEMA_RECORD_PREFIX = "attestation-schema"
+const CREDENTIAL_AUTHORITY_PREFIX = "attestation-authority"
+const CREDENTIAL_NAME = "legal-attestation-test"
+const SCHEMA_NAME = "jurisdiction"
 
 // SAS Program ID (official)
 const SAS_PROGRAM_ID = new PublicKey("22zoJMtdu4tQc2PzL74ZUT7FrwgB1Udec8DdW4yw4BdG")
@@ -29,6 +35,21 @@ describe("LegalAttestation Tests", () => {
     let configPda: PublicKey
     let configBump: number
 
+    let credentialAuthorityPda: PublicKey
+    let credentialPda: PublicKey
+    let schemaPda: PublicKey
+    let schemaRecordPda: PublicKey
+
+    const findRequestPda = (user: PublicKey, requestId: string): PublicKey =>
+        PublicKey.findProgramAddressSync(
+            [
+                Buffer.from(ATTESTATION_REQUEST_PREFIX),
+                user.toBuffer(),
+                createHash("sha256").update(requestId).digest(),
+            ],
+            program.programId
+        )[0]
+
     before(async () => {
         // Calculate PDA
         [configPda, configBump] = PublicKey.findProgramAddressSync(
@@ -36,6 +57,25 @@ describe("LegalAttestation Tests", () => {
             program.programId
         )
 
+        // SAS PDAs: credential per (authority, name), schema per (credential, name, version)
+        // The credential authority is the program's own PDA, not the admin key
+        [credentialAuthorityPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from(CREDENTIAL_AUTHORITY_PREFIX)],
+            program.programId
+        );
+        [credentialPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("credential"), credentialAuthorityPda.toBuffer(), Buffer.from(CREDENTIAL_NAME)],
+            SAS_PROGRAM_ID
+        );
+        [schemaPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("schema"), credentialPda.toBuffer(), Buffer.from(SCHEMA_NAME), Buffer.from([1])],
+            SAS_PROGRAM_ID
+        );
+        [schemaRecordPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from(SCHEMA_RECORD_PREFIX), schemaPda.toBuffer()],
+            program.programId
+        )
+
         // Airdrop SOL to test wallets
         const airdropAdmin = await provider.connection.requestAirdrop(
             ADMIN_WALLET.publicKey,
@@ -203,8 +243,170 @@ describe("LegalAttestation Tests", () => {
         })
     })
 
+    describe("Schema Registry", () => {
+        it("should create a credential and a registered schema", async () => {
+            await program.methods
+                .createCredential(CREDENTIAL_NAME)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    config: configPda,
+                    credentialAuthority: credentialAuthorityPda,
+                    credential: credentialPda,
+                    sasProgram: SAS_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            await program.methods
+                .createSchema(SCHEMA_NAME, "Jurisdiction attestation", Buffer.from([12]), ["jurisdiction"])
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    config: configPda,
+                    credentialAuthority: credentialAuthorityPda,
+                    credential: credentialPda,
+                    schema: schemaPda,
+                    schemaRecord: schemaRecordPda,
+                    sasProgram: SAS_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            const record = await program.account.schemaRecord.fetch(schemaRecordPda)
+            assert.ok(record.schema.equals(schemaPda))
+            assert.equal(record.name, SCHEMA_NAME)
+            assert.equal(record.active, true)
+            assert.equal(record.fee, null)
+
+            console.log("✅ Schema created and registered:", schemaPda.toBase58())
+        })
+
+        it("should pause and resume a schema", async () => {
+            await program.methods
+                .pauseSchema()
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    config: configPda,
+                    schemaRecord: schemaRecordPda,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            const requestId = "paused-request-" + Date.now()
+            try {
+                await program.methods
+                    .requestAttestation(schemaPda, requestId)
+                    .accountsPartial({
+                        user: USER_WALLET.publicKey,
+                        config: configPda,
+                        schemaRecord: schemaRecordPda,
+                        request: findRequestPda(USER_WALLET.publicKey, requestId),
+                        systemProgram: web3.SystemProgram.programId,
+                    })
+                    .signers([USER_WALLET])
+                    .rpc()
+                assert.fail("Should have thrown error")
+            } catch (err: any) {
+                assert.include(err.message, "SchemaInactive")
+            }
+
+            await program.methods
+                .resumeSchema()
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    config: configPda,
+                    schemaRecord: schemaRecordPda,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            const record = await program.account.schemaRecord.fetch(schemaRecordPda)
+            assert.equal(record.active, true)
+
+            console.log("✅ Paused schema rejected requests, resumed")
+        })
+
+        it("should reject requests for unknown schemas", async () => {
+            const unknownSchema = Keypair.generate().publicKey
+            const requestId = "unknown-request-" + Date.now()
+            try {
+                await program.methods
+                    .requestAttestation(unknownSchema, requestId)
+                    .accountsPartial({
+                        user: USER_WALLET.publicKey,
+                        config: configPda,
+                        schemaRecord: PublicKey.findProgramAddressSync(
+                            [Buffer.from(SCHEMA_RECORD_PREFIX), unknownSchema.toBuffer()],
+                            program.programId
+                        )[0],
+                        request: findRequestPda(USER_WALLET.publicKey, requestId),
+                        systemProgram: web3.SystemProgram.programId,
+                    })
+                    .signers([USER_WALLET])
+                    .rpc()
+                assert.fail("Should have thrown error")
+            } catch (err: any) {
+                assert.include(err.message, "AccountNotInitialized")
+                console.log("✅ Correctly rejected unknown schema")
+            }
+        })
+
+        it("should keep credential signers in sync with operators", async () => {
+            // SAS Credential: discriminator (1) + authority (32) + name (4 + len) + signers (4 + 32 * n)
+            const fetchCredentialSigners = async (): Promise<string[]> => {
+                const data = (await provider.connection.getAccountInfo(credentialPda))!.data
+                let offset = 1 + 32
+                offset += 4 + data.readUInt32LE(offset)
+                const count = data.readUInt32LE(offset)
+                offset += 4
+                return Array.from({ length: count }, (_, i) =>
+                    new PublicKey(data.subarray(offset + i * 32, offset + (i + 1) * 32)).toBase58()
+                )
+            }
+
+            const signer = Keypair.generate().publicKey
+            const accounts = {
+                authority: ADMIN_WALLET.publicKey,
+                config: configPda,
+                credentialAuthority: credentialAuthorityPda,
+                credential: credentialPda,
+                sasProgram: SAS_PROGRAM_ID,
+                systemProgram: web3.SystemProgram.programId,
+            }
+
+            await program.methods
+                .addCredentialSigner(signer)
+                .accountsPartial(accounts)
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            let config = await program.account.attestationConfig.fetch(configPda)
+            assert.deepEqual(
+                await fetchCredentialSigners(),
+                [credentialAuthorityPda, ...config.operators].map((key) => key.toBase58())
+            )
+            assert.ok(config.operators.some((key) => key.equals(signer)))
+
+            await program.methods
+                .removeCredentialSigner(signer)
+                .accountsPartial(accounts)
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            config = await program.account.attestationConfig.fetch(configPda)
+            assert.deepEqual(
+                await fetchCredentialSigners(),
+                [credentialAuthorityPda, ...config.operators].map((key) => key.toBase58())
+            )
+            assert.ok(!config.operators.some((key) => key.equals(signer)))
+
+            console.log("✅ Credential signers follow operators")
+        })
+    })
+
     describe("Request Attestation (User Flow)", () => {
-        it("should allow user to request attestation and pay fee", async () => {
+        it("should escrow the fee in a request and close it on reject", async () => {
             // Set a known fee first
             const attestationFee = new BN(0.01 * LAMPORTS_PER_SOL)
             await program.methods
@@ -216,38 +418,77 @@ describe("LegalAttestation Tests", () => {
                 .signers([ADMIN_WALLET])
                 .rpc()
 
-            const userBalanceBefore = await provider.connection.getBalance(USER_WALLET.publicKey)
-            const feeRecipientBalanceBefore = await provider.connection.getBalance(FEE_RECIPIENT.publicKey)
-
-            // Mock schema pubkey (in real scenario, this would be a SAS schema)
-            const mockSchema = Keypair.generate().publicKey
             const requestId = "request-" + Date.now()
+            const requestPda = findRequestPda(USER_WALLET.publicKey, requestId)
 
             await program.methods
-                .requestAttestation(mockSchema, requestId)
+                .requestAttestation(schemaPda, requestId)
                 .accountsPartial({
                     user: USER_WALLET.publicKey,
                     config: configPda,
-                    feeRecipient: FEE_RECIPIENT.publicKey,
+                    schemaRecord: schemaRecordPda,
+                    request: requestPda,
                     systemProgram: web3.SystemProgram.programId,
                 })
                 .signers([USER_WALLET])
                 .rpc()
 
-            const userBalanceAfter = await provider.connection.getBalance(USER_WALLET.publicKey)
-            const feeRecipientBalanceAfter = await provider.connection.getBalance(FEE_RECIPIENT.publicKey)
-
-            const userPaid = userBalanceBefore - userBalanceAfter
-            const feeReceived = feeRecipientBalanceAfter - feeRecipientBalanceBefore
+            const request = await program.account.attestationRequest.fetch(requestPda)
+            const requestInfo = await provider.connection.getAccountInfo(requestPda)
+            const rent = await provider.connection.getMinimumBalanceForRentExemption(requestInfo!.data.length)
 
             console.log("✅ Attestation requested")
             console.log("  Request ID:", requestId)
-            console.log("  Schema:", mockSchema.toBase58())
-            console.log("  User paid:", userPaid / LAMPORTS_PER_SOL, "SOL (includes tx fee)")
-            console.log("  Fee recipient received:", feeReceived / LAMPORTS_PER_SOL, "SOL")
+            console.log("  Request:", requestPda.toBase58())
+            console.log("  Schema:", schemaPda.toBase58())
+            console.log("  Escrowed:", request.amount.toNumber() / LAMPORTS_PER_SOL, "SOL")
 
-            // Verify fee was transferred
-            assert.equal(feeReceived, attestationFee.toNumber(), "Fee recipient should receive exact fee")
+            // Verify fee is escrowed in the request account
+            assert.equal(request.amount.toNumber(), attestationFee.toNumber())
+            assert.ok(request.user.equals(USER_WALLET.publicKey))
+            assert.ok(request.schema.equals(schemaPda))
+            assert.equal(requestInfo!.lamports - rent, attestationFee.toNumber(), "Request should hold exact fee")
+
+            // Paying the same request_id twice fails
+            try {
+                await program.methods
+                    .requestAttestation(schemaPda, requestId)
+                    .accountsPartial({
+                        user: USER_WALLET.publicKey,
+                        config: configPda,
+                        schemaRecord: schemaRecordPda,
+                        request: requestPda,
+                        systemProgram: web3.SystemProgram.programId,
+                    })
+                    .signers([USER_WALLET])
+                    .rpc()
+                assert.fail("Duplicate request should fail")
+            } catch (e: any) {
+                assert.notEqual(e.message, "Duplicate request should fail")
+            }
+
+            // Rejecting closes the request, the user gets the fee and rent back
+            const userBalanceBefore = await provider.connection.getBalance(USER_WALLET.publicKey)
+
+            await program.methods
+                .rejectAttestationRequest()
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    config: configPda,
+                    role: null,
+                    request: requestPda,
+                    user: USER_WALLET.publicKey,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc()
+
+            const userBalanceAfter = await provider.connection.getBalance(USER_WALLET.publicKey)
+            const rejected = await program.account.attestationRequest.fetchNullable(requestPda)
+
+            assert.equal(userBalanceAfter - userBalanceBefore, attestationFee.toNumber() + rent, "User should get the fee and rent back")
+            assert.isNull(rejected, "Rejected request should be closed")
+
+            console.log("✅ Attestation request rejected and refunded")
         })
 
         it("should work with zero fee", async () => {
@@ -261,21 +502,38 @@ describe("LegalAttestation Tests", () => {
                 .signers([ADMIN_WALLET])
                 .rpc()
 
-            const mockSchema = Keypair.generate().publicKey
             const requestId = "free-request-" + Date.now()
+            const requestPda = findRequestPda(USER_WALLET.publicKey, requestId)
 
             await program.methods
-                .requestAttestation(mockSchema, requestId)
+                .requestAttestation(schemaPda, requestId)
                 .accountsPartial({
                     user: USER_WALLET.publicKey,
                     config: configPda,
-                    feeRecipient: FEE_RECIPIENT.publicKey,
+                    schemaRecord: schemaRecordPda,
+                    request: requestPda,
                     systemProgram: web3.SystemProgram.programId,
                 })
                 .signers([USER_WALLET])
                 .rpc()
 
             console.log("✅ Free attestation request succeeded")
+
+            // The user can only refund once the request timed out
+            try {
+                await program.methods
+                    .refundAttestationRequest()
+                    .accountsPartial({
+                        user: USER_WALLET.publicKey,
+                        request: requestPda,
+                    })
+                    .signers([USER_WALLET])
+                    .rpc()
+                assert.fail("Should have thrown error")
+            } catch (err: any) {
+                assert.include(err.message, "RequestNotExpired")
+                console.log("✅ Early self-refund rejected")
+            }
         })
     })
 
@@ -1,219 +1,876 @@
 import { Legaldid } from '../../target/types/legaldid'
 import {
+    BN,
     Program,
     web3,
     workspace,
     setProvider,
     AnchorProvider,
 } from '@coral-xyz/anchor'
-import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, TOKEN_2022_PROGRAM_ID } from '@solana/spl-token'
+import {
+    ASSOCIATED_TOKEN_PROGRAM_ID,
+    getAssociatedTokenAddressSync,
+    getTokenMetadata,
+    TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token'
 import { PublicKey } from '@solana/web3.js'
 import { assert } from 'chai';
-import { ComputeBudgetProgram } from '@solana/web3.js';
-
-const { SYSVAR_RENT_PUBKEY } = web3
+import { ComputeBudgetProgram, Ed25519Program } from '@solana/web3.js';
+import { randomBytes } from 'crypto';
+import {
+    findCollectionMintPda,
+    findDidMintPda,
+    findLdidBlacklistPda,
+    findOrderPda,
+    findProjectPda,
+    LEGACY_PROJECT_ID,
+} from '../../scripts/svm/did/pda'
+import { TOKEN_METADATA_PROGRAM_ID } from './utils/constants'
+import { getCollectionMasterEditionAddress, getCollectionMetadataAddress } from './utils/utils'
+
+const { SYSVAR_RENT_PUBKEY, SYSVAR_INSTRUCTIONS_PUBKEY } = web3
 
 // Constants
 const ADMIN_WALLET = web3.Keypair.generate();
 const USER_WALLET = web3.Keypair.generate();
+const CLAIMER_WALLET = web3.Keypair.generate();
+
+const WALLET_INDEX_PREFIX = "nt-wallet-idx-v5";
+const BLOCKED_WALLET_PREFIX = "nt-blk-wallet-v5";
 
-const NON_TRANSFERABLE_PROJECT_PREFIX = "nt-proj-v5";
-const NON_TRANSFERABLE_NFT_MINT_PREFIX = "nt-nft-mint-v5";
+// pause_flags in the program
+const PAUSE_USER = 1;
+const PAUSE_OPERATOR = 2;
 
-describe("Optimized Flow Test (No DIDStatusAccount)", () => {
+// Set in initialize
+const MINT_PRICE = 100;
+
+describe("LegalDID", () => {
     const provider = AnchorProvider.env();
     setProvider(provider)
     const program = workspace.Legaldid as Program<Legaldid>;
 
-    const rnsId = "rns-" + Date.now();
-    const index = "idx-" + Date.now();
-    const merkleRoot = "0x764e6372e05f4db05595276214e74f047a6562f19bf6cc3bb35a53ac892c3ce3";
+    // A fresh project per run, so the suite doesn't depend on the legacy project 0
+    const projectId = new BN(Date.now());
+    const project = findProjectPda(program.programId, projectId);
+    const collectionMint = findCollectionMintPda(program.programId, projectId);
 
-    let nonTransferableProject: PublicKey;
-    let nonTransferableProjectMint: PublicKey;
-    let nonTransferableNftMint: PublicKey;
-    let userTokenAccount: PublicKey;
+    // Only the legacy project authority (or the upgrade authority) may create other project ids
+    const legacyProject = findProjectPda(program.programId, LEGACY_PROJECT_ID);
 
-    before(async () => {
-        // Calculate PDA
-        [nonTransferableProject] = PublicKey.findProgramAddressSync(
-            [Buffer.from(NON_TRANSFERABLE_PROJECT_PREFIX)],
-            program.programId
-        );
-
-        [nonTransferableProjectMint] = PublicKey.findProgramAddressSync(
-            [Buffer.from("nt-project-mint-v5")],
-            program.programId
-        );
-
-        [nonTransferableNftMint] = PublicKey.findProgramAddressSync(
-            [Buffer.from(NON_TRANSFERABLE_NFT_MINT_PREFIX), Buffer.from(index)],
-            program.programId
-        );
-
-        userTokenAccount = getAssociatedTokenAddressSync(
-            nonTransferableNftMint,
-            USER_WALLET.publicKey,
-            false,
-            TOKEN_2022_PROGRAM_ID
-        );
-
-        // Airdrop SOL
-        const airdropAdmin = await provider.connection.requestAirdrop(
-            ADMIN_WALLET.publicKey,
-            10 * web3.LAMPORTS_PER_SOL
-        );
-        await provider.connection.confirmTransaction(airdropAdmin);
-
-        const airdropUser = await provider.connection.requestAirdrop(
-            USER_WALLET.publicKey,
-            5 * web3.LAMPORTS_PER_SOL
-        );
-        await provider.connection.confirmTransaction(airdropUser);
+    const computeIx = () => ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 });
 
-        console.log("Airdropped SOL to wallets");
-    });
+    const randomMerkleRoot = () => randomBytes(32).toString("hex");
+
+    const findPda = (seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+    const walletIndexPda = (wallet: PublicKey) =>
+        findPda([Buffer.from(WALLET_INDEX_PREFIX), project.toBuffer(), wallet.toBuffer()]);
+
+    const walletBlacklistPda = (wallet: PublicKey) =>
+        findPda([Buffer.from(BLOCKED_WALLET_PREFIX), project.toBuffer(), wallet.toBuffer()]);
 
-    it("Initialize project", async () => {
-        const ix = ComputeBudgetProgram.setComputeUnitLimit({ units: 1_000_000 });
+    /** Accounts shared by airdrop, claim_did and airdrop_batch for one DID */
+    const didAccounts = (orderId: string, wallet: PublicKey, merkleRoot: string) => {
+        const nftMint = findDidMintPda(program.programId, projectId, orderId);
+        return {
+            order: findOrderPda(program.programId, projectId, orderId),
+            walletIndex: walletIndexPda(wallet),
+            walletBlacklist: walletBlacklistPda(wallet),
+            ldidBlacklist: findLdidBlacklistPda(program.programId, projectId, merkleRoot),
+            nonTransferableNftMint: nftMint,
+            userTokenAccount: getAssociatedTokenAddressSync(nftMint, wallet, false, TOKEN_2022_PROGRAM_ID),
+        };
+    };
 
-        await program.methods
-            .initialize({
-                name: "Legal DID",
-                symbol: 'LDID',
-                baseUri: "https://api.rns.id/api/v2/portal/identity/nft/"
+    const authorizeMint = (orderId: string, payer: web3.Keypair, wallet: PublicKey) =>
+        program.methods
+            .authorizeMint(orderId, wallet)
+            .accountsPartial({
+                payer: payer.publicKey,
+                nonTransferableProject: project,
+                order: findOrderPda(program.programId, projectId, orderId),
+                walletBlacklist: walletBlacklistPda(wallet),
+                systemProgram: web3.SystemProgram.programId,
             })
+            .signers([payer])
+            .rpc();
+
+    const airdrop = (orderId: string, wallet: PublicKey, merkleRoot: string) =>
+        program.methods
+            .airdrop(orderId, wallet, merkleRoot)
             .accountsPartial({
                 authority: ADMIN_WALLET.publicKey,
-                nonTransferableProject: nonTransferableProject,
-                nonTransferableProjectMint: nonTransferableProjectMint,
+                nonTransferableProject: project,
+                role: null,
+                paymentMintConfig: null,
+                ...didAccounts(orderId, wallet, merkleRoot),
+                userAccount: wallet,
+                collectionMint,
+                nftMetadata: null,
+                nftMasterEdition: null,
+                collectionMetadata: null,
+                collectionMasterEdition: null,
+                tokenMetadataProgram: null,
+                sysvarInstructions: null,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 tokenProgram: TOKEN_2022_PROGRAM_ID,
                 systemProgram: web3.SystemProgram.programId,
                 rent: SYSVAR_RENT_PUBKEY,
             })
             .signers([ADMIN_WALLET])
-            .preInstructions([ix])
+            .preInstructions([computeIx()])
             .rpc();
 
-        console.log("✅ Project initialized");
-    });
-
-    it("AuthorizeMint - user pays fee and emits event", async () => {
-        console.log("\n=== AuthorizeMint Test ===");
-
-        const userBalanceBefore = await provider.connection.getBalance(USER_WALLET.publicKey);
-        const adminBalanceBefore = await provider.connection.getBalance(ADMIN_WALLET.publicKey);
-
-        // User pays to request mint
-        const tx = await program.methods
-            .authorizeMint(rnsId, merkleRoot, index)
+    const setPaused = (paused: number) =>
+        program.methods
+            .setPaused(paused)
             .accountsPartial({
-                payer: USER_WALLET.publicKey,
-                nonTransferableProject: nonTransferableProject,
-                feeRecipient: ADMIN_WALLET.publicKey, // fee_recipient defaults to admin
-                systemProgram: web3.SystemProgram.programId,
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: project,
             })
-            .signers([USER_WALLET])
+            .signers([ADMIN_WALLET])
             .rpc();
 
-        const userBalanceAfter = await provider.connection.getBalance(USER_WALLET.publicKey);
-        const adminBalanceAfter = await provider.connection.getBalance(ADMIN_WALLET.publicKey);
+    const expectError = async (promise: Promise<unknown>, code: string) => {
+        try {
+            await promise;
+            assert.fail(`Should have failed with ${code}`);
+        } catch (err: any) {
+            assert.include(err.toString(), code);
+        }
+    };
 
-        const userPaid = (userBalanceBefore - userBalanceAfter) / 1e9;
-        const adminReceived = (adminBalanceAfter - adminBalanceBefore) / 1e9;
+    const fetchOrder = (orderId: string) =>
+        program.account.orderAccount.fetch(findOrderPda(program.programId, projectId, orderId));
 
-        console.log("✅ AuthorizeMint success");
-        console.log("  💸 User paid:", userPaid.toFixed(8), "SOL (includes tx fee)");
-        console.log("  💰 Admin received:", adminReceived.toFixed(8), "SOL");
-        console.log("  📝 Transaction:", tx);
+    // DIDs issued along the way, burned / revoked at the end
+    const userOrderId = "order-user-" + Date.now();
+    const batchWallets = [web3.Keypair.generate().publicKey, web3.Keypair.generate().publicKey];
+    const batchOrderIds = batchWallets.map((_, i) => `order-batch-${i}-${Date.now()}`);
 
-        // Verify admin received mint_price (100 lamports, set in initialize)
-        assert(adminReceived > 0 || adminBalanceAfter >= adminBalanceBefore, "Admin should receive fee");
-    });
+    before(async () => {
+        for (const wallet of [ADMIN_WALLET, USER_WALLET, CLAIMER_WALLET]) {
+            const signature = await provider.connection.requestAirdrop(
+                wallet.publicKey,
+                10 * web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(signature);
+        }
 
-    it("Airdrop NFT (one step, merkle_root in metadata)", async () => {
-        console.log("\n=== Optimized Airdrop Test ===");
-        console.log("RNS ID:", rnsId);
-        console.log("Index:", index);
+        console.log("Airdropped SOL to wallets");
 
-        const adminBalanceBefore = await provider.connection.getBalance(ADMIN_WALLET.publicKey);
+        // Fresh validator: the admin creates the legacy project so it can create the test projects
+        if (!(await provider.connection.getAccountInfo(legacyProject))) {
+            await program.methods
+                .initialize({
+                    projectId: new BN(LEGACY_PROJECT_ID),
+                    name: "Legal DID",
+                    symbol: 'LDID',
+                    baseUri: "https://api.rns.id/api/v2/portal/identity/nft/",
+                    metaplexMetadata: false,
+                })
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: legacyProject,
+                    nonTransferableProjectMint: findCollectionMintPda(program.programId, LEGACY_PROJECT_ID),
+                    legacyProject: null,
+                    programData: null,
+                    collectionMetadata: null,
+                    collectionMasterEdition: null,
+                    tokenMetadataProgram: null,
+                    sysvarInstructions: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([ADMIN_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            console.log("Initialized legacy project");
+        }
+    });
 
-        const ix = ComputeBudgetProgram.setComputeUnitLimit({ units: 1_000_000 });
+    describe("Project setup", () => {
+        it("Initialize - rejects a new project id from anyone but the legacy or upgrade authority", async () => {
+            const squattedId = projectId.addn(2);
+
+            await expectError(
+                program.methods
+                    .initialize({
+                        projectId: squattedId,
+                        name: "Legal DID",
+                        symbol: 'LDID',
+                        baseUri: "https://api.rns.id/api/v2/portal/identity/nft/",
+                        metaplexMetadata: false,
+                    })
+                    .accountsPartial({
+                        authority: USER_WALLET.publicKey,
+                        nonTransferableProject: findProjectPda(program.programId, squattedId),
+                        nonTransferableProjectMint: findCollectionMintPda(program.programId, squattedId),
+                        legacyProject,
+                        programData: null,
+                        collectionMetadata: null,
+                        collectionMasterEdition: null,
+                        tokenMetadataProgram: null,
+                        sysvarInstructions: null,
+                        tokenProgram: TOKEN_2022_PROGRAM_ID,
+                        systemProgram: web3.SystemProgram.programId,
+                        rent: SYSVAR_RENT_PUBKEY,
+                    })
+                    .signers([USER_WALLET])
+                    .preInstructions([computeIx()])
+                    .rpc(),
+                "Unauthorized"
+            );
+
+            console.log("✅ Project id squatting rejected");
+        });
+
+        it("Initialize project", async () => {
+            await program.methods
+                .initialize({
+                    projectId,
+                    name: "Legal DID",
+                    symbol: 'LDID',
+                    baseUri: "https://api.rns.id/api/v2/portal/identity/nft/",
+                    metaplexMetadata: false,
+                })
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    nonTransferableProjectMint: collectionMint,
+                    legacyProject,
+                    programData: null,
+                    collectionMetadata: null,
+                    collectionMasterEdition: null,
+                    tokenMetadataProgram: null,
+                    sysvarInstructions: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([ADMIN_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            const state = await program.account.projectAccount.fetch(project);
+            assert.ok(state.projectId.eq(projectId));
+            assert.equal(state.mintPrice.toNumber(), MINT_PRICE);
+            assert.ok(state.reclaimTimeoutSlots.gtn(0), "Reclaim timeout should default to non-zero");
+            assert.equal(state.pendingEscrow.toNumber(), 0);
+
+            console.log("✅ Project initialized:", project.toBase58());
+        });
+
+        it("Migrate - rejects a project already at the current layout", async () => {
+            await expectError(
+                program.methods
+                    .migrateProject(projectId)
+                    .accountsPartial({
+                        authority: ADMIN_WALLET.publicKey,
+                        nonTransferableProject: project,
+                        systemProgram: web3.SystemProgram.programId,
+                    })
+                    .signers([ADMIN_WALLET])
+                    .rpc(),
+                "AlreadyMigrated"
+            );
+
+            console.log("✅ Current layout not migrated again");
+        });
+
+        it("Set base URI - updates the project and the collection metadata", async () => {
+            const baseUri = "https://api.rns.id/api/v3/portal/identity/nft/metadata/";
+
+            await program.methods
+                .setBaseUri(baseUri)
+                .accountsPartial({
+                    nonTransferableProject: project,
+                    authority: ADMIN_WALLET.publicKey,
+                    collectionMint,
+                    collectionMetadata: null,
+                    tokenMetadataProgram: null,
+                    sysvarInstructions: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            const state = await program.account.projectAccount.fetch(project);
+            const metadata = await getTokenMetadata(provider.connection, collectionMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+            assert.equal(state.baseUri, baseUri);
+            assert.equal(metadata!.uri, baseUri);
+
+            console.log("✅ Base URI updated");
+        });
+    });
 
-        await program.methods
-            .airdrop(rnsId, USER_WALLET.publicKey, merkleRoot, index)
-            .accountsPartial({
+    describe("Orders", () => {
+        it("AuthorizeMint escrows the fee, airdrop fulfils the order", async () => {
+            const merkleRoot = randomMerkleRoot();
+
+            await authorizeMint(userOrderId, USER_WALLET, USER_WALLET.publicKey);
+
+            let order = await fetchOrder(userOrderId);
+            let state = await program.account.projectAccount.fetch(project);
+            assert.deepEqual(order.status, { pending: {} });
+            assert.equal(order.amount.toNumber(), MINT_PRICE);
+            assert.ok(order.wallet.equals(USER_WALLET.publicKey));
+            assert.equal(state.pendingEscrow.toNumber(), MINT_PRICE);
+
+            await airdrop(userOrderId, USER_WALLET.publicKey, merkleRoot);
+
+            const accounts = didAccounts(userOrderId, USER_WALLET.publicKey, merkleRoot);
+            order = await fetchOrder(userOrderId);
+            state = await program.account.projectAccount.fetch(project);
+            const walletIndex = await program.account.walletIndex.fetch(accounts.walletIndex);
+            const mintInfo = await provider.connection.getAccountInfo(accounts.nonTransferableNftMint);
+
+            assert.deepEqual(order.status, { fulfilled: {} });
+            assert.equal(state.pendingEscrow.toNumber(), 0, "Fulfilled order should leave escrow");
+            assert.equal(state.totalMinted.toNumber(), 1);
+            assert.ok(walletIndex.mint.equals(accounts.nonTransferableNftMint));
+            assert.ok(mintInfo !== null && mintInfo.owner.equals(TOKEN_2022_PROGRAM_ID), "Should be a Token-2022 mint");
+
+            // The order can only be fulfilled once
+            await expectError(airdrop(userOrderId, USER_WALLET.publicKey, merkleRoot), "OrderNotPending");
+
+            console.log("✅ Order paid and fulfilled");
+        });
+
+        it("Refund returns the fee to the payer", async () => {
+            const orderId = "order-refund-" + Date.now();
+            await authorizeMint(orderId, USER_WALLET, web3.Keypair.generate().publicKey);
+
+            const payerBefore = await provider.connection.getBalance(USER_WALLET.publicKey);
+
+            await program.methods
+                .refundOrder(orderId)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    role: null,
+                    order: findOrderPda(program.programId, projectId, orderId),
+                    payer: USER_WALLET.publicKey,
+                    paymentMintConfig: null,
+                    paymentMint: null,
+                    vault: null,
+                    payerTokenAccount: null,
+                    tokenProgram: null,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            const payerAfter = await provider.connection.getBalance(USER_WALLET.publicKey);
+            const order = await fetchOrder(orderId);
+            const state = await program.account.projectAccount.fetch(project);
+
+            assert.equal(payerAfter - payerBefore, MINT_PRICE);
+            assert.deepEqual(order.status, { refunded: {} });
+            assert.equal(state.pendingEscrow.toNumber(), 0);
+
+            console.log("✅ Order refunded");
+        });
+
+        it("Withdraw keeps pending escrow, payer reclaims after the timeout", async () => {
+            const orderId = "order-reclaim-" + Date.now();
+            const orderPda = findOrderPda(program.programId, projectId, orderId);
+            await authorizeMint(orderId, USER_WALLET, web3.Keypair.generate().publicKey);
+
+            await program.methods
+                .withdraw()
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    destination: ADMIN_WALLET.publicKey,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            const projectInfo = await provider.connection.getAccountInfo(project);
+            const rent = await provider.connection.getMinimumBalanceForRentExemption(projectInfo!.data.length);
+            assert.equal(projectInfo!.lamports - rent, MINT_PRICE, "Pending order's fee should stay in the project");
+
+            const reclaim = () =>
+                program.methods
+                    .reclaimOrder(orderId)
+                    .accountsPartial({
+                        payer: USER_WALLET.publicKey,
+                        nonTransferableProject: project,
+                        order: orderPda,
+                        paymentMintConfig: null,
+                        paymentMint: null,
+                        vault: null,
+                        payerTokenAccount: null,
+                        tokenProgram: null,
+                        systemProgram: web3.SystemProgram.programId,
+                    })
+                    .signers([USER_WALLET])
+                    .rpc();
+
+            await expectError(reclaim(), "ReclaimTooEarly");
+
+            await program.methods
+                .setReclaimTimeout(new BN(1))
+                .accountsPartial({
+                    nonTransferableProject: project,
+                    authority: ADMIN_WALLET.publicKey,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            const paidAt = (await fetchOrder(orderId)).slot.toNumber();
+            while ((await provider.connection.getSlot("confirmed")) <= paidAt) {
+                await new Promise((resolve) => setTimeout(resolve, 400));
+            }
+
+            await reclaim();
+
+            const order = await fetchOrder(orderId);
+            const state = await program.account.projectAccount.fetch(project);
+            assert.deepEqual(order.status, { cancelled: {} });
+            assert.equal(state.pendingEscrow.toNumber(), 0);
+
+            console.log("✅ Escrow held back from withdraw and reclaimed");
+        });
+    });
+
+    describe("Blacklist", () => {
+        it("Blocked wallets can't order", async () => {
+            const wallet = web3.Keypair.generate().publicKey;
+            const blacklistAccounts = {
                 authority: ADMIN_WALLET.publicKey,
-                nonTransferableProject: nonTransferableProject,
-                nonTransferableNftMint: nonTransferableNftMint,
-                userAccount: USER_WALLET.publicKey,
-                userTokenAccount: userTokenAccount,
-                collectionMint: nonTransferableProjectMint,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-                tokenProgram: TOKEN_2022_PROGRAM_ID,
-                systemProgram: web3.SystemProgram.programId,
-                rent: SYSVAR_RENT_PUBKEY,
-            })
-            .signers([ADMIN_WALLET])
-            .preInstructions([ix])
-            .rpc();
+                nonTransferableProject: project,
+                role: null,
+                blacklistEntry: walletBlacklistPda(wallet),
+            };
+
+            await program.methods
+                .blockWallet(wallet)
+                .accountsPartial({ ...blacklistAccounts, systemProgram: web3.SystemProgram.programId })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            await expectError(authorizeMint("order-blocked-" + Date.now(), USER_WALLET, wallet), "WalletBlacklisted");
+
+            await program.methods
+                .unblockWallet(wallet)
+                .accountsPartial(blacklistAccounts)
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            await authorizeMint("order-unblocked-" + Date.now(), USER_WALLET, wallet);
+
+            console.log("✅ Wallet blacklist enforced");
+        });
+
+        it("Blocked LDIDs can't be airdropped, whatever the merkle root spelling", async () => {
+            const merkleRoot = randomMerkleRoot();
+            const wallet = web3.Keypair.generate().publicKey;
+            const orderId = "order-ldid-" + Date.now();
+            const blacklistAccounts = {
+                authority: ADMIN_WALLET.publicKey,
+                nonTransferableProject: project,
+                role: null,
+                blacklistEntry: findLdidBlacklistPda(program.programId, projectId, merkleRoot),
+            };
+
+            await program.methods
+                .blockLdid("0x" + merkleRoot.toUpperCase())
+                .accountsPartial({ ...blacklistAccounts, systemProgram: web3.SystemProgram.programId })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            await authorizeMint(orderId, USER_WALLET, wallet);
+            await expectError(airdrop(orderId, wallet, merkleRoot), "LdidBlacklisted");
+
+            await program.methods
+                .unblockLdid(merkleRoot)
+                .accountsPartial(blacklistAccounts)
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+            await airdrop(orderId, wallet, merkleRoot);
+
+            console.log("✅ LDID blacklist enforced");
+        });
+    });
 
-        const adminBalanceAfter = await provider.connection.getBalance(ADMIN_WALLET.publicKey);
-        const cost = (adminBalanceBefore - adminBalanceAfter) / 1e9;
+    describe("Pause", () => {
+        it("User and operator paths pause independently", async () => {
+            const wallet = web3.Keypair.generate().publicKey;
+            const orderId = "order-pause-" + Date.now();
 
-        console.log("✅ NFT minted");
-        console.log("  💸 Cost:", cost.toFixed(8), "SOL");
-        console.log("  💸 Cost (USD @ $140):", (cost * 140).toFixed(2), "USD");
+            await setPaused(PAUSE_USER);
+            await expectError(authorizeMint(orderId, USER_WALLET, wallet), "ProgramPaused");
 
-        // Verify NFT Mint exists
-        const mintInfo = await provider.connection.getAccountInfo(nonTransferableNftMint);
-        assert(mintInfo !== null, "NFT Mint should exist");
-        assert(mintInfo.owner.equals(TOKEN_2022_PROGRAM_ID), "Should be Token-2022");
-        console.log("  ✅ Token-2022 NFT Mint verified");
+            await setPaused(PAUSE_OPERATOR);
+            await authorizeMint(orderId, USER_WALLET, wallet);
+            await expectError(airdrop(orderId, wallet, randomMerkleRoot()), "ProgramPaused");
+
+            await setPaused(0);
+            await airdrop(orderId, wallet, randomMerkleRoot());
+
+            console.log("✅ Pause flags enforced");
+        });
     });
 
-    it("User burn NFT", async () => {
-        console.log("\n=== User Burn Test ===");
+    describe("Claim DID", () => {
+        /** Borsh MintVoucher: project, order_id, wallet, merkle_root, expiry */
+        const serializeVoucher = (orderId: string, wallet: PublicKey, merkleRoot: string, expiry: BN) => {
+            const string = (value: string) => {
+                const bytes = Buffer.from(value);
+                const len = Buffer.alloc(4);
+                len.writeUInt32LE(bytes.length);
+                return Buffer.concat([len, bytes]);
+            };
+            return Buffer.concat([
+                project.toBuffer(),
+                string(orderId),
+                wallet.toBuffer(),
+                string(merkleRoot),
+                expiry.toArrayLike(Buffer, "le", 8),
+            ]);
+        };
+
+        const claim = (orderId: string, merkleRoot: string, expiry: BN, voucherSigner: web3.Keypair) => {
+            const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+                privateKey: voucherSigner.secretKey,
+                message: serializeVoucher(orderId, CLAIMER_WALLET.publicKey, merkleRoot, expiry),
+            });
+
+            return program.methods
+                .claimDid(orderId, merkleRoot, expiry)
+                .accountsPartial({
+                    user: CLAIMER_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    voucherSignerRole: null,
+                    paymentMintConfig: null,
+                    ...didAccounts(orderId, CLAIMER_WALLET.publicKey, merkleRoot),
+                    collectionMint,
+                    instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+                    nftMetadata: null,
+                    nftMasterEdition: null,
+                    collectionMetadata: null,
+                    collectionMasterEdition: null,
+                    tokenMetadataProgram: null,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([CLAIMER_WALLET])
+                .preInstructions([computeIx(), ed25519Ix])
+                .rpc();
+        };
+
+        it("User mints with an admin-signed Ed25519 voucher", async () => {
+            const orderId = "order-claim-" + Date.now();
+            const merkleRoot = randomMerkleRoot();
+            const expiry = new BN(Math.floor(Date.now() / 1000) + 600);
+
+            await authorizeMint(orderId, CLAIMER_WALLET, CLAIMER_WALLET.publicKey);
+
+            // Vouchers signed by an unknown key are rejected
+            await expectError(claim(orderId, merkleRoot, expiry, web3.Keypair.generate()), "SignatureVerificationFailed");
+
+            await claim(orderId, merkleRoot, expiry, ADMIN_WALLET);
+
+            const accounts = didAccounts(orderId, CLAIMER_WALLET.publicKey, merkleRoot);
+            const order = await fetchOrder(orderId);
+            const walletIndex = await program.account.walletIndex.fetch(accounts.walletIndex);
+            assert.deepEqual(order.status, { fulfilled: {} });
+            assert.ok(walletIndex.mint.equals(accounts.nonTransferableNftMint));
+
+            console.log("✅ DID claimed with voucher");
+        });
+    });
 
-        const adminBalanceBefore = await provider.connection.getBalance(ADMIN_WALLET.publicKey);
-        const userBalanceBefore = await provider.connection.getBalance(USER_WALLET.publicKey);
+    describe("Airdrop batch", () => {
+        it("Mints several paid orders in one instruction", async () => {
+            const entries = batchWallets.map((wallet, i) => ({
+                orderId: batchOrderIds[i],
+                wallet,
+                merkleRoot: randomMerkleRoot(),
+            }));
+            for (const entry of entries) {
+                await authorizeMint(entry.orderId, USER_WALLET, entry.wallet);
+            }
+
+            const mintedBefore = (await program.account.projectAccount.fetch(project)).totalMinted.toNumber();
+
+            // order, wallet_index, wallet_blacklist, ldid_blacklist, nft_mint, user_account, user_token_account
+            const remainingAccounts = entries.flatMap((entry) => {
+                const accounts = didAccounts(entry.orderId, entry.wallet, entry.merkleRoot);
+                return [
+                    { pubkey: accounts.order, isSigner: false, isWritable: true },
+                    { pubkey: accounts.walletIndex, isSigner: false, isWritable: true },
+                    { pubkey: accounts.walletBlacklist, isSigner: false, isWritable: false },
+                    { pubkey: accounts.ldidBlacklist, isSigner: false, isWritable: false },
+                    { pubkey: accounts.nonTransferableNftMint, isSigner: false, isWritable: true },
+                    { pubkey: entry.wallet, isSigner: false, isWritable: true },
+                    { pubkey: accounts.userTokenAccount, isSigner: false, isWritable: true },
+                ];
+            });
+
+            await program.methods
+                .airdropBatch(entries)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    role: null,
+                    collectionMint,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .remainingAccounts(remainingAccounts)
+                .signers([ADMIN_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            const state = await program.account.projectAccount.fetch(project);
+            assert.equal(state.totalMinted.toNumber(), mintedBefore + entries.length);
+            for (const entry of entries) {
+                const order = await fetchOrder(entry.orderId);
+                assert.deepEqual(order.status, { fulfilled: {} });
+            }
+
+            console.log("✅ Batch minted", entries.length, "DIDs");
+        });
+    });
 
-        const ix = ComputeBudgetProgram.setComputeUnitLimit({ units: 1_000_000 });
+    describe("Update DID", () => {
+        const updateDid = (nftMint: PublicKey, merkleRoot: string) =>
+            program.methods
+                .updateDid(merkleRoot)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    role: null,
+                    nonTransferableNftMint: nftMint,
+                    nftMetadata: null,
+                    tokenMetadataProgram: null,
+                    sysvarInstructions: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .rpc();
+
+        it("Rewrites a DID's uri, but not the collection's", async () => {
+            const merkleRoot = randomMerkleRoot();
+            const nftMint = findDidMintPda(program.programId, projectId, userOrderId);
+
+            await updateDid(nftMint, merkleRoot);
+
+            const state = await program.account.projectAccount.fetch(project);
+            const metadata = await getTokenMetadata(provider.connection, nftMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+            assert.equal(metadata!.uri, `${state.baseUri}${merkleRoot}.json`);
+
+            await expectError(updateDid(collectionMint, merkleRoot), "InvalidMintAccount");
+
+            console.log("✅ DID updated, collection untouched");
+        });
+    });
 
-        await program.methods
-            .burn(rnsId, index)
-            .accountsPartial({
-                nftOwner: USER_WALLET.publicKey,
-                authority: ADMIN_WALLET.publicKey,
-                nonTransferableProject: nonTransferableProject,
-                userTokenAccount: userTokenAccount,
-                nonTransferableNftMint: nonTransferableNftMint,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-                tokenProgram: TOKEN_2022_PROGRAM_ID,
-                systemProgram: web3.SystemProgram.programId,
-            })
-            .signers([USER_WALLET])
-            .preInstructions([ix])
-            .rpc();
+    describe("Burn and revoke", () => {
+        it("Holder burns their DID", async () => {
+            const nftMint = findDidMintPda(program.programId, projectId, userOrderId);
+            const userTokenAccount = getAssociatedTokenAddressSync(nftMint, USER_WALLET.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+            await program.methods
+                .burn()
+                .accountsPartial({
+                    nftOwner: USER_WALLET.publicKey,
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    walletIndex: walletIndexPda(USER_WALLET.publicKey),
+                    userTokenAccount,
+                    nonTransferableNftMint: nftMint,
+                    nftMetadata: null,
+                    nftMasterEdition: null,
+                    collectionMetadata: null,
+                    tokenMetadataProgram: null,
+                    sysvarInstructions: null,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([USER_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            const state = await program.account.projectAccount.fetch(project);
+            assert.isNull(await provider.connection.getAccountInfo(userTokenAccount), "Token account should be closed");
+            assert.isNull(await provider.connection.getAccountInfo(nftMint), "Mint account should be closed");
+            assert.isNull(await provider.connection.getAccountInfo(walletIndexPda(USER_WALLET.publicKey)), "Wallet index should be closed");
+            assert.equal(state.totalBurned.toNumber(), 1);
+
+            console.log("✅ DID burned");
+        });
+
+        it("Admin revokes a DID", async () => {
+            const wallet = batchWallets[0];
+            const nftMint = findDidMintPda(program.programId, projectId, batchOrderIds[0]);
+            const userTokenAccount = getAssociatedTokenAddressSync(nftMint, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+            await program.methods
+                .revokeDid(1)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: project,
+                    role: null,
+                    wallet,
+                    walletIndex: walletIndexPda(wallet),
+                    userTokenAccount,
+                    nonTransferableNftMint: nftMint,
+                    nftMetadata: null,
+                    collectionMint: null,
+                    collectionMetadata: null,
+                    tokenMetadataProgram: null,
+                    sysvarInstructions: null,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            const state = await program.account.projectAccount.fetch(project);
+            assert.isNull(await provider.connection.getAccountInfo(nftMint), "Mint account should be closed");
+            assert.isNull(await provider.connection.getAccountInfo(walletIndexPda(wallet)), "Wallet index should be closed");
+            assert.equal(state.totalRevoked.toNumber(), 1);
+
+            console.log("✅ DID revoked");
+        });
+    });
 
-        const adminBalanceAfter = await provider.connection.getBalance(ADMIN_WALLET.publicKey);
-        const userBalanceAfter = await provider.connection.getBalance(USER_WALLET.publicKey);
-        
-        const adminRecovered = (adminBalanceAfter - adminBalanceBefore) / 1e9;
-        const userRecovered = (userBalanceAfter - userBalanceBefore) / 1e9;
-
-        console.log("✅ NFT burned");
-        console.log("  💰 Admin recovered (Mint rent):", adminRecovered.toFixed(8), "SOL");
-        console.log("  💰 User recovered (ATA rent - tx fee):", userRecovered.toFixed(8), "SOL");
-
-        // Verify Token Account is closed
-        const tokenAccountInfo = await provider.connection.getAccountInfo(userTokenAccount);
-        assert(tokenAccountInfo === null, "Token account should be closed");
-        console.log("  ✅ Token account closed");
-
-        // Verify Mint account is closed
-        const mintInfo = await provider.connection.getAccountInfo(nonTransferableNftMint);
-        assert(mintInfo === null, "Mint account should be closed");
-        console.log("  ✅ Mint account closed");
+    describe("Metaplex mode", () => {
+        const metaplexProjectId = projectId.addn(1);
+        const metaplexProject = findProjectPda(program.programId, metaplexProjectId);
+        const metaplexCollectionMint = findCollectionMintPda(program.programId, metaplexProjectId);
+        const wallet = web3.Keypair.generate().publicKey;
+        const orderId = "order-metaplex-" + Date.now();
+        const nftMint = findDidMintPda(program.programId, metaplexProjectId, orderId);
+        const userTokenAccount = getAssociatedTokenAddressSync(nftMint, wallet, false, TOKEN_2022_PROGRAM_ID);
+
+        it("Airdrop creates Metaplex metadata, revoke burns without the holder", async () => {
+            const collectionMetadata = await getCollectionMetadataAddress(metaplexCollectionMint);
+            const collectionMasterEdition = await getCollectionMasterEditionAddress(metaplexCollectionMint);
+            const nftMetadata = await getCollectionMetadataAddress(nftMint);
+            const nftMasterEdition = await getCollectionMasterEditionAddress(nftMint);
+
+            await program.methods
+                .initialize({
+                    projectId: metaplexProjectId,
+                    name: "Legal DID",
+                    symbol: 'LDID',
+                    baseUri: "https://api.rns.id/api/v2/portal/identity/nft/",
+                    metaplexMetadata: true,
+                })
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: metaplexProject,
+                    nonTransferableProjectMint: metaplexCollectionMint,
+                    legacyProject,
+                    programData: null,
+                    collectionMetadata,
+                    collectionMasterEdition,
+                    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+                    sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([ADMIN_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            const walletIndex = findPda([Buffer.from(WALLET_INDEX_PREFIX), metaplexProject.toBuffer(), wallet.toBuffer()]);
+            const order = findOrderPda(program.programId, metaplexProjectId, orderId);
+            const merkleRoot = randomMerkleRoot();
+
+            await program.methods
+                .authorizeMint(orderId, wallet)
+                .accountsPartial({
+                    payer: USER_WALLET.publicKey,
+                    nonTransferableProject: metaplexProject,
+                    order,
+                    walletBlacklist: findPda([Buffer.from(BLOCKED_WALLET_PREFIX), metaplexProject.toBuffer(), wallet.toBuffer()]),
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([USER_WALLET])
+                .rpc();
+
+            await program.methods
+                .airdrop(orderId, wallet, merkleRoot)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: metaplexProject,
+                    role: null,
+                    order,
+                    paymentMintConfig: null,
+                    walletIndex,
+                    walletBlacklist: findPda([Buffer.from(BLOCKED_WALLET_PREFIX), metaplexProject.toBuffer(), wallet.toBuffer()]),
+                    ldidBlacklist: findLdidBlacklistPda(program.programId, metaplexProjectId, merkleRoot),
+                    nonTransferableNftMint: nftMint,
+                    userAccount: wallet,
+                    userTokenAccount,
+                    collectionMint: metaplexCollectionMint,
+                    nftMetadata,
+                    nftMasterEdition,
+                    collectionMetadata,
+                    collectionMasterEdition,
+                    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+                    sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .signers([ADMIN_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            const metadataInfo = await provider.connection.getAccountInfo(nftMetadata);
+            const editionInfo = await provider.connection.getAccountInfo(nftMasterEdition);
+            const tokenBalance = await provider.connection.getTokenAccountBalance(userTokenAccount);
+            assert.ok(metadataInfo !== null && metadataInfo.owner.equals(TOKEN_METADATA_PROGRAM_ID), "Metaplex metadata should exist");
+            assert.ok(editionInfo !== null, "Master edition should exist");
+            assert.equal(tokenBalance.value.amount, "1");
+
+            // The holder doesn't sign - Token Metadata's BurnV1 would need them
+            await program.methods
+                .revokeDid(1)
+                .accountsPartial({
+                    authority: ADMIN_WALLET.publicKey,
+                    nonTransferableProject: metaplexProject,
+                    role: null,
+                    wallet,
+                    walletIndex,
+                    userTokenAccount,
+                    nonTransferableNftMint: nftMint,
+                    nftMetadata,
+                    collectionMint: metaplexCollectionMint,
+                    collectionMetadata,
+                    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+                    sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: web3.SystemProgram.programId,
+                })
+                .signers([ADMIN_WALLET])
+                .preInstructions([computeIx()])
+                .rpc();
+
+            assert.isNull(await provider.connection.getAccountInfo(nftMint), "Mint account should be closed");
+            assert.isNull(await provider.connection.getAccountInfo(walletIndex), "Wallet index should be closed");
+
+            console.log("✅ Metaplex DID minted and revoked");
+        });
     });
 });